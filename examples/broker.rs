@@ -78,9 +78,7 @@ async fn main() -> anyhow::Result<()> {
 
     // create broker and proxy
     let broker = DefaultBroker::<Number>::new().spawn().await?;
-    let subscribe_proxy = broker
-        .proxy::<DefaultBroker<Number>, Subscribe<Number>>()
-        .await;
+    let subscribe_proxy = broker.proxy::<Subscribe<Number>>().await;
 
     // subscribe to broker
     try_join!(
@@ -91,9 +89,7 @@ async fn main() -> anyhow::Result<()> {
 
     let broker_clone = broker.clone();
     let h = tokio::task::spawn(async move {
-        let broker_publish_proxy = broker_clone
-            .proxy::<DefaultBroker<Number>, Publish<Number>>()
-            .await;
+        let broker_publish_proxy = broker_clone.proxy::<Publish<Number>>().await;
         for i in 0..10 {
             info!("Publish: {}", i);
             broker_publish_proxy
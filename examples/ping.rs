@@ -11,12 +11,12 @@ use xtor::actor::{addr::WeakAddr, context::Context, message::Handler, runner::Ac
 struct Ping(isize);
 
 #[xtor::message(result = "()")]
-struct SetPingAddress(WeakAddr);
+struct SetPingAddress(WeakAddr<PingActor>);
 struct PingActor {
     sleeper: isize,
     n: isize,
     counter: AtomicUsize,
-    ping_address: OnceCell<WeakAddr>,
+    ping_address: OnceCell<WeakAddr<PingActor>>,
 }
 
 #[async_trait::async_trait]
@@ -34,11 +34,10 @@ impl Handler<Ping> for PingActor {
         match self.ping_address.get().expect("fail to get").upgrade() {
             Some(addr) => {
                 let n = self.n;
-                let _ = tokio::task::spawn(async move {
-                    addr.call_unblock::<PingActor, Ping>(Ping(msg.0 + n))
-                        .await
-                        .await
-                });
+                let _ =
+                    tokio::task::spawn(
+                        async move { addr.call_unblock(Ping(msg.0 + n)).await.await },
+                    );
                 tokio::time::sleep(std::time::Duration::from_millis(self.sleeper as u64)).await;
                 self.counter
                     .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
@@ -86,11 +85,11 @@ async fn main() -> anyhow::Result<()> {
 
     // set their address to each other
     try_join!(
-        ping_addr.call::<PingActor, SetPingAddress>(SetPingAddress(pong_addr.downgrade())),
-        pong_addr.call::<PingActor, SetPingAddress>(SetPingAddress(ping_addr.downgrade())),
+        ping_addr.call(SetPingAddress(pong_addr.downgrade())),
+        pong_addr.call(SetPingAddress(ping_addr.downgrade())),
     )?;
 
-    ping_addr.call::<PingActor, Ping>(Ping(0)).await?;
+    ping_addr.call(Ping(0)).await?;
     tokio::time::sleep(std::time::Duration::from_millis(10000)).await;
     ping_addr.stop(Ok(()));
     pong_addr.stop(Ok(()));
@@ -1,8 +1,9 @@
-use std::sync::atomic::AtomicUsize;
-
 use anyhow::Result;
 use tracing::info;
-use xtor::actor::{message::Handler, runner::Actor};
+use xtor::actor::{
+    message::{Handler, MutHandler},
+    runner::Actor,
+};
 
 #[xtor::message(result = "()")]
 struct AddOne;
@@ -12,17 +13,16 @@ struct AddOne;
 struct Print;
 
 struct CounterActor {
-    counter: AtomicUsize,
+    counter: usize,
 }
 
 #[async_trait::async_trait]
 impl Actor for CounterActor {}
 
 #[async_trait::async_trait]
-impl Handler<AddOne> for CounterActor {
-    async fn handle(&self, _ctx: &xtor::actor::context::Context, _msg: AddOne) -> Result<()> {
-        self.counter
-            .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+impl MutHandler<AddOne> for CounterActor {
+    async fn handle(&mut self, _ctx: &xtor::actor::context::Context, _msg: AddOne) -> Result<()> {
+        self.counter += 1;
         Ok(())
     }
 }
@@ -35,7 +35,7 @@ impl Handler<Print> for CounterActor {
         fields(addr = self.get_name_or_id_string(_ctx).as_str())
     )]
     async fn handle(&self, _ctx: &xtor::actor::context::Context, _msg: Print) -> Result<()> {
-        info!("{}", self.counter.load(std::sync::atomic::Ordering::SeqCst));
+        info!("{}", self.counter);
         Ok(())
     }
 }
@@ -45,15 +45,11 @@ async fn main() -> anyhow::Result<()> {
     tracing_subscriber::fmt::init();
 
     // create actor
-    let addr = CounterActor {
-        counter: AtomicUsize::new(0),
-    }
-    .spawn()
-    .await?;
+    let addr = CounterActor { counter: 0 }.spawn().await?;
     addr.set_name("Counter Actor").await;
 
     // create proxy
-    let proxy = addr.proxy::<CounterActor, AddOne>().await;
+    let proxy = addr.proxy_mut::<AddOne>().await;
     tokio::task::spawn(async move {
         let start = std::time::Instant::now();
         while start.elapsed().as_secs() < 10 {
@@ -65,9 +61,7 @@ async fn main() -> anyhow::Result<()> {
     tokio::task::spawn(async move {
         let start = std::time::Instant::now();
         while start.elapsed().as_secs() < 10 {
-            addr.call::<CounterActor, Print>(Print)
-                .await
-                .expect("fail to call");
+            addr.call(Print).await.expect("fail to call");
             tokio::time::sleep(std::time::Duration::from_millis(100)).await;
         }
     });
@@ -25,5 +25,5 @@ impl Handler<Hello> for HelloActor {
 async fn main() -> Result<()> {
     let hello_actor = HelloActor;
     let hello_actor_address = hello_actor.spawn().await?;
-    hello_actor_address.call::<HelloActor, Hello>(Hello).await
+    hello_actor_address.call(Hello).await
 }
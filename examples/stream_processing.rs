@@ -5,7 +5,7 @@ use tracing::info;
 use xtor::{
     actor::{context::Context, message::Handler, runner::Actor},
     broker::Subscribe,
-    utils::default_broker::{DefaultBroker, StreamBroker},
+    utils::default_broker::StreamBroker,
 };
 
 #[derive(Debug, Clone)]
@@ -90,12 +90,8 @@ async fn main() -> anyhow::Result<()> {
 
     // subscribe to broker
     try_join!(
-        broker.call::<DefaultBroker<Number>, Subscribe<Number>>(
-            Subscribe::from_addr::<EvenSubscriptor>(&even).await,
-        ),
-        broker.call::<DefaultBroker<Number>, Subscribe<Number>>(
-            Subscribe::from_addr::<BigNumberSubscriptor>(&big).await,
-        )
+        broker.call(Subscribe::from_addr::<EvenSubscriptor>(&even).await,),
+        broker.call(Subscribe::from_addr::<BigNumberSubscriptor>(&big).await,)
     )?;
 
     h.await?.map_err(|e| e)
@@ -3,7 +3,8 @@
 // food has two types: normal and poisoned.
 // normal food is good for human.
 // poisoned food will kill human.
-// normal_hospital is a supervisor that can respawn(lol) a human.
+// normal_hospital is a supervisor that can respawn(lol) a human, a respawned
+// human is a brand new one.
 
 // another thing is called virus.
 // virus is contagious.
@@ -45,7 +46,6 @@ impl ActorRestart for Human {
     )]
     async fn on_restart(&self, addr: &WeakAddr) {
         warn!("\thospital saved {}", addr.get_name_or_id_string());
-        self.0.store(false, std::sync::atomic::Ordering::SeqCst);
     }
 }
 
@@ -123,20 +123,18 @@ async fn main() -> anyhow::Result<()> {
 
     // create proxy to supervisor actor
     let (normal_hospital_proxy, virus_hospital_proxy) = join!(
-        normal_hospital.proxy::<DefaultSupervisor, Supervise>(),
-        virus_hospital.proxy::<DefaultSupervisor, Supervise>()
+        normal_hospital.proxy::<Supervise>(),
+        virus_hospital.proxy::<Supervise>()
     );
 
     // link actors to supervisor
-    let alice = Human::default()
-        .spawn_supervisable()
+    let alice = Human::spawn_supervised_with(Human::default)
         .await?
         .chain_link_to_supervisor(&normal_hospital_proxy)
         .await?
         .chain_link_to_supervisor(&virus_hospital_proxy)
         .await?;
-    let bob = Human::default()
-        .spawn_supervisable()
+    let bob = Human::spawn_supervised_with(Human::default)
         .await?
         .chain_link_to_supervisor(&normal_hospital_proxy)
         .await?;
@@ -144,15 +142,13 @@ async fn main() -> anyhow::Result<()> {
     // thus virus could not come out so he decided to only paying the normal
     // hospital
 
-    let carl = Human::default()
-        .spawn_supervisable()
+    let carl = Human::spawn_supervised_with(Human::default)
         .await?
         .chain_link_to_supervisor(&normal_hospital_proxy)
         .await?
         .chain_link_to_supervisor(&virus_hospital_proxy)
         .await?;
-    let david = Human::default()
-        .spawn_supervisable()
+    let david = Human::spawn_supervised_with(Human::default)
         .await?
         .chain_link_to_supervisor(&normal_hospital_proxy)
         .await?
@@ -185,7 +181,7 @@ async fn main() -> anyhow::Result<()> {
                 .get(rng.gen_range(0..foods.len()))
                 .expect("fail to get food")
                 .clone();
-            let _ = selected_human.call::<Human, Eat>(Eat(selected_food)).await;
+            let _ = selected_human.call(Eat(selected_food)).await;
         }
     });
 
@@ -198,7 +194,7 @@ async fn main() -> anyhow::Result<()> {
             let selected_human = humans
                 .get(rng.gen_range(0..humans.len()))
                 .expect("fail to get human");
-            let _ = selected_human.call::<Human, Virus>(Virus).await;
+            let _ = selected_human.call(Virus).await;
         }
     });
 
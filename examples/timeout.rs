@@ -34,10 +34,7 @@ async fn main() -> anyhow::Result<()> {
     let start = std::time::Instant::now();
     while start.elapsed().as_secs() < 10 {
         let oracle1_number = oracle1
-            .call_timeout::<Oracle, GetOracleNumber>(
-                GetOracleNumber,
-                std::time::Duration::from_millis(500),
-            )
+            .call_timeout(GetOracleNumber, std::time::Duration::from_millis(500))
             .await?;
         match oracle1_number {
             Some(o1) => {
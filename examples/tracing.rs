@@ -31,5 +31,5 @@ async fn main() -> Result<()> {
     tracing_subscriber::fmt::init();
     let hello_actor = HelloActor;
     let hello_actor_address = hello_actor.spawn().await?;
-    hello_actor_address.call::<HelloActor, Hello>(Hello).await
+    hello_actor_address.call(Hello).await
 }
@@ -35,9 +35,9 @@ async fn main() -> anyhow::Result<()> {
     let start = std::time::Instant::now();
     while start.elapsed().as_secs() < 10 {
         let (oracle1_number, oracle2_number, oracle3_number) = join!(
-            oracle1.call_unblock::<Oracle, GetOracleNumber>(GetOracleNumber),
-            oracle2.call_unblock::<Oracle, GetOracleNumber>(GetOracleNumber),
-            oracle3.call_unblock::<Oracle, GetOracleNumber>(GetOracleNumber)
+            oracle1.call_unblock(GetOracleNumber),
+            oracle2.call_unblock(GetOracleNumber),
+            oracle3.call_unblock(GetOracleNumber)
         );
         // use tokio select to unblock with timeout interval
         tokio::select! {
@@ -1,63 +1,272 @@
 use std::{
     hash::Hash,
+    marker::PhantomData,
     pin::Pin,
     sync::{Arc, Weak},
     time::Duration,
 };
 
 use anyhow::Result;
-use futures::{
-    channel::{mpsc, oneshot},
-    future::Shared,
-    Future,
-};
+use futures::{channel::oneshot, Future, FutureExt};
 
 use super::{
     context::Context,
-    message::{Handler, Message},
-    proxy::{Proxy, ProxyFnBlock},
-    runner::{ActorID, ACTOR_ID_NAME},
-    supervisor::Restart,
-    ACTOR_ID_HANDLE,
+    link::{spawn_link, spawn_monitor, Down, LinkEnd},
+    mailbox::{MailboxSender, Priority},
+    message::{Dispatch, Exclusive, Handler, Message, MutHandler, Shared},
+    proxy::Proxy,
+    runner::{Actor, ActorID, ActorStopped, ErrorPolicy, ExitReason, ExitSignal, StopMode},
+    supervisor::{HandlerPanic, Restart},
+    system::ActorSystem,
 };
 use crate::Supervise;
 
-pub(crate) type ExecFuture<'a> = Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>;
+pub(crate) type ExecFuture<'a> =
+    Pin<Box<dyn Future<Output = std::result::Result<(), ExecError>> + Send + 'a>>;
 pub(crate) type ExecFn =
     Box<dyn FnOnce(Arc<dyn std::any::Any + Send + Sync>, &Context) -> ExecFuture + Send + 'static>;
+pub(crate) type ExecMutFn = Box<
+    dyn for<'a> FnOnce(
+            Option<&'a mut (dyn std::any::Any + Send + Sync)>,
+            &'a Context,
+        ) -> ExecFuture<'a>
+        + Send
+        + 'static,
+>;
 
-/// default wait interval to 10ms
-/// you can set to a custom value
+/// default wait interval of `Addr::is_stopped` in the systems created after
+/// it is set
+#[deprecated(note = "use `ActorSystemConfig::stop_wait_interval`")]
 pub static mut ACTOR_STOP_WAIT_INTERVAL: Duration = std::time::Duration::from_millis(10);
 
+/// error returned by an exec function to the runner
+pub struct ExecError {
+    pub error: anyhow::Error,
+    /// overrides the error policy of the actor
+    pub policy: Option<ErrorPolicy>,
+}
+
+impl ExecError {
+    /// the error and the policy to apply, falling back to `default`
+    /// a panicked handler is never `ReplyOnly`
+    pub(crate) fn resolve(self, default: ErrorPolicy) -> (anyhow::Error, ErrorPolicy) {
+        let policy = match self.policy.unwrap_or(default) {
+            ErrorPolicy::ReplyOnly if self.error.is::<HandlerPanic>() => ErrorPolicy::Restart,
+            policy => policy,
+        };
+        (self.error, policy)
+    }
+}
+
+impl From<anyhow::Error> for ExecError {
+    fn from(error: anyhow::Error) -> Self {
+        Self {
+            error,
+            policy: None,
+        }
+    }
+}
+
+/// build the exec function which runs the handler of `A` for `msg`
+/// and sends the result back through `tx` if there is one
+/// the caller always gets the error, the runner gets a copy of it
+pub(crate) fn handler_exec<A: Handler<T>, T: Message>(
+    msg: T,
+    tx: Option<oneshot::Sender<Result<T::Result>>>,
+) -> ExecFn {
+    let tx = ReplyTx(tx);
+    Box::new(move |actor, ctx| {
+        Box::pin(async move {
+            match actor.as_ref().downcast_ref::<A>() {
+                Some(handler) => reply::<T>(tx, guard_panic(handler.handle(ctx, msg)).await),
+                None => Err(reply_error(
+                    tx,
+                    anyhow::anyhow!(
+                        "error: {} trying to handle a message in actor which you didn't implement \
+                         the handler trait {} for it",
+                        std::any::type_name_of_val(&actor),
+                        std::any::type_name::<dyn Handler::<T>>()
+                    ),
+                    None,
+                )),
+            }
+        })
+    })
+}
+
+/// like `handler_exec` but for a handler which needs `&mut self`
+pub(crate) fn mut_handler_exec<A: MutHandler<T>, T: Message>(
+    msg: T,
+    tx: Option<oneshot::Sender<Result<T::Result>>>,
+) -> ExecMutFn {
+    let tx = ReplyTx(tx);
+    Box::new(move |actor, ctx| {
+        Box::pin(async move {
+            // `None` if the actor is still shared, e.g. by an `ExecFn` which kept it
+            let actor = match actor {
+                Some(actor) => actor,
+                None => {
+                    return Err(reply_error(
+                        tx,
+                        anyhow::anyhow!(
+                            "error: the actor is shared by other handlers, {} needs it alone",
+                            std::any::type_name::<dyn MutHandler::<T>>()
+                        ),
+                        Some(ErrorPolicy::ReplyOnly),
+                    ));
+                }
+            };
+            match actor.downcast_mut::<A>() {
+                Some(handler) => reply::<T>(tx, guard_panic(handler.handle(ctx, msg)).await),
+                None => Err(reply_error(
+                    tx,
+                    anyhow::anyhow!(
+                        "error: trying to handle a message in actor which you didn't implement \
+                         the handler trait {} for it",
+                        std::any::type_name::<dyn MutHandler::<T>>()
+                    ),
+                    None,
+                )),
+            }
+        })
+    })
+}
+
+#[cfg(not(feature = "supervisor_catch_unwind"))]
+pub(crate) async fn guard_panic<R>(fut: impl Future<Output = Result<R>>) -> Result<R> {
+    fut.await
+}
+
+/// a panic becomes a `HandlerPanic` error
+#[cfg(feature = "supervisor_catch_unwind")]
+pub(crate) async fn guard_panic<R>(fut: impl Future<Output = Result<R>>) -> Result<R> {
+    std::panic::AssertUnwindSafe(fut)
+        .catch_unwind()
+        .await
+        .unwrap_or_else(|payload| Err(HandlerPanic::from_payload(payload).into()))
+}
+
+/// the reply channel of a message
+/// if the message is dropped without being handled (the actor is stopped)
+/// the caller gets `ActorStopped`
+struct ReplyTx<R>(Option<oneshot::Sender<Result<R>>>);
+
+impl<R> Drop for ReplyTx<R> {
+    fn drop(&mut self) {
+        if let Some(tx) = self.0.take() {
+            let _ = tx.send(Err(ActorStopped.into()));
+        }
+    }
+}
+
+fn reply<T: Message>(
+    mut tx: ReplyTx<T::Result>,
+    res: Result<T::Result>,
+) -> std::result::Result<(), ExecError> {
+    match res {
+        Ok(res) => {
+            if let Some(tx) = tx.0.take() {
+                let _ = tx.send(Ok(res));
+            }
+            Ok(())
+        }
+        Err(e) => Err(reply_error(tx, e, T::ERROR_POLICY)),
+    }
+}
+
+fn reply_error<R>(
+    mut tx: ReplyTx<R>,
+    error: anyhow::Error,
+    policy: Option<ErrorPolicy>,
+) -> ExecError {
+    let error = match tx.0.take() {
+        Some(tx) => {
+            let copy = match error.downcast_ref::<HandlerPanic>() {
+                Some(panic) => panic.clone().into(),
+                None => anyhow::anyhow!("{:#}", error),
+            };
+            let _ = tx.send(Err(error));
+            copy
+        }
+        None => error,
+    };
+    ExecError { error, policy }
+}
+
+/// the runner task is aborted if the signal is dropped without a reason
+fn exit_reason(rx_exit: &ExitSignal) -> Option<ExitReason> {
+    rx_exit
+        .clone()
+        .now_or_never()
+        .map(|reason| reason.unwrap_or(ExitReason::Killed))
+}
+
 // Event type
 pub enum Event {
-    Stop(Result<()>),
+    Stop(Result<()>, StopMode),
     Exec(ExecFn),
+    /// exec with exclusive access to the actor
+    ExecMut(ExecMutFn),
     Restart,
     AddSupervisor(Proxy<Restart>),
+    /// a linked actor exited
+    LinkExit(ActorID, ExitReason),
 }
 
+/// marker of an untyped address
+/// `Addr` is short for `Addr<Untyped>`
+pub struct Untyped;
+
 /// the address of an actor
 /// remember to use WeakAddr to avoid memory leak
 /// clone as you want
-#[derive(Clone)]
-pub struct Addr {
+///
+/// `Addr<A>` knows the type of the actor, so only messages which `A` could
+/// handle are accepted, `Addr` (erased by `Addr::erase`) needs the actor type
+/// on every call.
+pub struct Addr<A = Untyped> {
     pub id: ActorID,
-    pub(crate) tx: Arc<mpsc::UnboundedSender<Event>>,
-    pub(crate) rx_exit: Shared<oneshot::Receiver<()>>,
+    pub(crate) tx: Arc<MailboxSender>,
+    pub(crate) rx_exit: ExitSignal,
+    pub(crate) system: ActorSystem,
+    pub(crate) _marker: PhantomData<fn() -> A>,
 }
 
-impl Addr {
+impl<A> Addr<A> {
+    pub(crate) fn new(
+        id: ActorID,
+        tx: Arc<MailboxSender>,
+        rx_exit: ExitSignal,
+        system: ActorSystem,
+    ) -> Self {
+        Self {
+            id,
+            tx,
+            rx_exit,
+            system,
+            _marker: PhantomData,
+        }
+    }
+
+    /// erase the type of the actor
+    pub fn erase(self) -> Addr {
+        Addr::new(self.id, self.tx, self.rx_exit, self.system)
+    }
+
+    /// the system the actor belongs to
+    pub fn system(&self) -> &ActorSystem {
+        &self.system
+    }
+
     /// link self to supervisor
     pub async fn link_to_supervisor(&self, proxy: &Proxy<Supervise>) -> Result<()> {
-        proxy.call(Supervise(self.clone())).await?;
+        proxy.call(Supervise(self.clone().erase())).await?;
         Ok(())
     }
 
     /// link self to supervisor chained version
     pub async fn chain_link_to_supervisor(self, proxy: &Proxy<Supervise>) -> Result<Self> {
-        proxy.call(Supervise(self.clone())).await?;
+        proxy.call(Supervise(self.clone().erase())).await?;
         Ok(self)
     }
 
@@ -73,73 +282,86 @@ impl Addr {
 
     /// get the name of the actor
     pub fn get_name(&self) -> Option<String> {
-        ACTOR_ID_NAME.get(&self.id)?.clone()
+        self.system.name(self.id)
     }
 
     /// explicitly add a supervisor
     /// this is useful when you want to create a custom supervisor
+    /// the system knows about it at once, so `ActorSystem::shutdown` stops the
+    /// supervisor after this actor
     pub async fn add_supervisor(&self, supervisor: Proxy<Restart>) {
-        let _ =
-            mpsc::UnboundedSender::clone(&*self.tx).start_send(Event::AddSupervisor(supervisor));
+        self.system.add_supervisor(self.id, supervisor.id);
+        let _ = self.tx.send_system(Event::AddSupervisor(supervisor));
     }
 
     /// send stop event to the actor
+    /// it is queued like a message: the messages already in the mailbox are
+    /// handled first, the ones sent after it are dropped and their callers get
+    /// `ActorStopped`
+    /// use `stop_with_mode(err, StopMode::Immediate)` to stop without handling
+    /// the queued messages
     pub fn stop(self, err: Result<()>) {
-        let _ = mpsc::UnboundedSender::clone(&*self.tx).start_send(Event::Stop(err));
+        let _ = self
+            .tx
+            .force_send(Event::Stop(err, StopMode::Immediate), Priority::Normal);
+    }
+
+    /// send stop event to the actor, it overtakes the queued messages and
+    /// `mode` decides what happens to them
+    pub fn stop_with_mode(self, err: Result<()>, mode: StopMode) {
+        let _ = self.tx.send_system(Event::Stop(err, mode));
+    }
+
+    /// safely kill the actor
+    /// the handlers in flight and the queued messages are dropped and their
+    /// callers get `ActorStopped`, `on_stop` still runs
+    pub fn kill(self) {
+        self.stop_with_mode(Ok(()), StopMode::DrainWithDeadline(Duration::ZERO));
     }
 
     /// Raw exec is not recommended to use, please use `call` or `send` instead
+    /// it never waits, the bound of the mailbox is ignored
     pub fn exec(self, f: ExecFn) {
-        mpsc::UnboundedSender::clone(&*self.tx)
-            .start_send(Event::Exec(f))
+        self.tx
+            .force_send(Event::Exec(f), Priority::Normal)
             .expect("send exec event failed");
     }
 
-    /// force to block the unblocked call
-    pub async fn call<A: Handler<T>, T: Message>(&self, msg: T) -> anyhow::Result<T::Result> {
-        self.call_unblock::<A, T>(msg).await.await?
-    }
-
-    /// unblocking call
-    /// you can await on the receiver to get the result when you need
-    pub async fn call_unblock<A: Handler<T>, T: Message>(
+    async fn call_unblock_handler<H: Dispatch<T, K>, T: Message, K>(
         &self,
         msg: T,
+        priority: Priority,
     ) -> oneshot::Receiver<anyhow::Result<T::Result>> {
         let (tx, rx) = oneshot::channel();
-        let _ = mpsc::UnboundedSender::clone(&*self.tx).start_send(Event::Exec(Box::new(
-            move |actor, ctx| {
-                Box::pin(async move {
-                    match actor.as_ref().downcast_ref::<A>() {
-                        Some(handler) => match handler.handle(ctx, msg).await {
-                            Ok(res) => {
-                                let _ = tx.send(Ok(res));
-                                Ok(())
-                            }
-                            Err(e) => Err(e),
-                        },
-                        None => Err(anyhow::anyhow!(
-                            "error: {} trying to handle a message in actor which you didn't \
-                             implement the handler trait {} for it",
-                            std::any::type_name_of_val(&actor),
-                            std::any::type_name::<dyn Handler::<T>>()
-                        )),
-                    }
-                })
-            },
-        )));
+        let _ = self.tx.send(H::exec_event(msg, Some(tx)), priority).await;
         rx
     }
 
-    /// Ok(None) means it is timeout
-    /// Ok(Some(res)) means it is not timeout
-    /// Err(e) means it is not timeout but with error occurred
-    pub async fn call_timeout<A: Handler<T>, T: Message>(
+    async fn send_handler<H: Dispatch<T, K>, T: Message, K>(
+        &self,
+        msg: T,
+        priority: Priority,
+    ) -> anyhow::Result<()> {
+        self.tx.send(H::exec_event(msg, None), priority).await?;
+        Ok(())
+    }
+
+    async fn try_call_handler<H: Dispatch<T, K>, T: Message, K>(
+        &self,
+        msg: T,
+    ) -> anyhow::Result<T::Result> {
+        let (tx, rx) = oneshot::channel();
+        self.tx
+            .try_send(H::exec_event(msg, Some(tx)), T::PRIORITY)?;
+        rx.await?
+    }
+
+    async fn call_timeout_handler<H: Dispatch<T, K>, T: Message, K>(
         &self,
         msg: T,
         timeout: Duration,
     ) -> anyhow::Result<Option<T::Result>> {
-        let chan = self.call_unblock::<A, T>(msg).await;
+        let chan = self.call_unblock_handler::<H, T, K>(msg, T::PRIORITY).await;
         tokio::select! {
             res = chan =>  {
                 res.map(|x| x.ok()).map_err(|e| e.into())
@@ -148,100 +370,286 @@ impl Addr {
         }
     }
 
-    /// create a proxy (like delegate in C#)
-    /// you only needs to care the lifetime and the type when you trying to
-    /// create when you calling to proxy, the type of actor is not needed.
-    pub async fn proxy<A: Handler<T>, T: Message>(&self) -> Proxy<T> {
-        let weak_tx = Arc::downgrade(&self.tx);
-        let inner: ProxyFnBlock<T> = Box::new(move |msg| {
-            let weak_tx = weak_tx.clone();
-            Box::pin(async move {
-                let (tx, rx) = oneshot::channel();
-                let ttx = weak_tx
-                    .upgrade()
-                    .ok_or_else(|| anyhow::anyhow!("error: proxy tx is dropped"))?;
-                mpsc::UnboundedSender::clone(&*ttx).start_send(Event::Exec(Box::new(
-                    move |actor, ctx| {
-                        Box::pin(async move {
-                            match actor.as_ref().downcast_ref::<A>() {
-                                Some(handler) => match handler.handle(ctx, msg).await {
-                                    Ok(res) => {
-                                        let _ = tx.send(Ok(res));
-                                        Ok(())
-                                    }
-                                    Err(e) => Err(e),
-                                },
-                                None => Err(anyhow::anyhow!(
-                                    "error: {} trying to handle a message in actor which you \
-                                     didn't implement the handler trait {} for it",
-                                    std::any::type_name_of_val(&actor),
-                                    std::any::type_name::<dyn Handler::<T>>()
-                                )),
-                            }
-                        })
-                    },
-                )))?;
-                Ok(rx)
-            })
-        });
-
-        Proxy {
+    fn proxy_handler<H: Dispatch<T, K>, T: Message, K>(&self) -> Proxy<T> {
+        Proxy::from_mailbox(self.id, Arc::downgrade(&self.tx), H::exec_event)
+    }
+
+    /// deliver `Down` to `watcher` when this actor exits
+    /// delivered at once if it is already stopped, dropped if the watcher
+    /// exits first
+    pub fn monitor<W: Handler<Down>>(&self, watcher: &Addr<W>) {
+        let id = self.id;
+        spawn_monitor(
+            self.rx_exit.clone(),
+            watcher.rx_exit.clone(),
+            watcher.proxy_handler::<W, Down, Shared>(),
+            move |reason| Some(Down { id, reason }),
+        );
+    }
+
+    /// link this actor with `other`
+    /// when one of them fails the other one is stopped with
+    /// `ExitReason::Linked`, or gets an `Exit` message if it traps exits
+    /// (see `ActorRunner::with_trap_exit`)
+    /// a normal exit only reaches the actors trapping exits
+    /// a link only fires once, a supervised actor restarted after a linked
+    /// exit is no longer linked
+    pub fn link<B>(&self, other: &Addr<B>) {
+        spawn_link(self.link_end(), other.link_end());
+    }
+
+    fn link_end(&self) -> LinkEnd {
+        LinkEnd {
             id: self.id,
-            proxy_inner: inner,
+            tx: Arc::downgrade(&self.tx),
+            rx_exit: self.rx_exit.clone(),
         }
     }
 
     /// check if it is stoped
-    /// the default interval is 10ms
+    /// waits for `ActorSystemConfig::stop_wait_interval`, default to 10ms
     pub async fn is_stopped(&self) -> bool {
         tokio::select! {
             _ = self.rx_exit.clone() => true,
-            _ = tokio::time::sleep(unsafe{ACTOR_STOP_WAIT_INTERVAL}) => false,
+            _ = tokio::time::sleep(self.system.config().stop_wait_interval) => false,
         }
     }
 
-    /// wait to stop
+    /// wait to stop and get the exit reason
     /// used in main method for blocking the main thread
-    pub async fn await_stop(&self) -> Result<()> {
-        self.rx_exit.clone().await.map_err(|err| err.into())
+    pub async fn await_stop(&self) -> ExitReason {
+        self.rx_exit.clone().await.unwrap_or(ExitReason::Killed)
+    }
+
+    /// the exit reason, None if it is still running
+    pub fn exit_reason(&self) -> Option<ExitReason> {
+        exit_reason(&self.rx_exit)
     }
 
     /// # Safety
     ///
-    /// may lead to deadlock and memory leak
+    /// may lead to deadlock and memory leak, `on_stop` is skipped
+    /// prefer `kill`, which only gets stuck on a handler never yielding
     /// None means it is already stopped
     /// Some(()) means it is stopped by this function call
     pub async unsafe fn force_stop(self) -> Option<()> {
-        let id = self.id;
-        ACTOR_ID_HANDLE.get(&id)?.abort();
-        ACTOR_ID_HANDLE.remove(&id);
-        Some(())
+        self.system.abort_actor(self.id)
     }
 
     /// set the name of the actor
-    /// better for debug
+    /// better for debug, use `register` to make it found by `whereis`
     pub async fn set_name<T: Into<String>>(&self, name: T) {
-        ACTOR_ID_NAME.insert(self.id, Some(name.into()));
+        self.system.names().insert(self.id, Some(name.into()));
     }
 
     /// downgrade to weak address
     /// for avoid cyclic reference for Arc
-    pub fn downgrade(&self) -> WeakAddr {
+    pub fn downgrade(&self) -> WeakAddr<A> {
         WeakAddr {
             id: self.id,
             _tx: Arc::downgrade(&self.tx),
             _rx_exit: self.rx_exit.clone(),
+            _system: self.system.clone(),
+            _marker: PhantomData,
         }
     }
 }
 
-impl std::fmt::Debug for Addr {
+impl<A: Actor> Addr<A> {
+    /// force to block the unblocked call
+    /// waits for a free slot if the mailbox is bounded and full
+    pub async fn call<T: Message, K>(&self, msg: T) -> anyhow::Result<T::Result>
+    where
+        A: Dispatch<T, K>,
+    {
+        self.call_unblock_handler::<A, T, K>(msg, T::PRIORITY)
+            .await
+            .await?
+    }
+
+    /// unblocking call
+    /// you can await on the receiver to get the result when you need
+    pub async fn call_unblock<T: Message, K>(
+        &self,
+        msg: T,
+    ) -> oneshot::Receiver<anyhow::Result<T::Result>>
+    where
+        A: Dispatch<T, K>,
+    {
+        self.call_unblock_handler::<A, T, K>(msg, T::PRIORITY).await
+    }
+
+    /// fire and forget, the result of the handler is dropped
+    /// returns as soon as the message is in the mailbox
+    /// waits for a free slot if the mailbox is bounded and full
+    pub async fn send<T: Message, K>(&self, msg: T) -> anyhow::Result<()>
+    where
+        A: Dispatch<T, K>,
+    {
+        self.send_handler::<A, T, K>(msg, T::PRIORITY).await
+    }
+
+    /// like `call` but queued in the lane of `priority` instead of the one
+    /// declared by the message
+    pub async fn call_with_priority<T: Message, K>(
+        &self,
+        msg: T,
+        priority: Priority,
+    ) -> anyhow::Result<T::Result>
+    where
+        A: Dispatch<T, K>,
+    {
+        self.call_unblock_handler::<A, T, K>(msg, priority)
+            .await
+            .await?
+    }
+
+    /// like `send` but queued in the lane of `priority` instead of the one
+    /// declared by the message
+    pub async fn send_with_priority<T: Message, K>(
+        &self,
+        msg: T,
+        priority: Priority,
+    ) -> anyhow::Result<()>
+    where
+        A: Dispatch<T, K>,
+    {
+        self.send_handler::<A, T, K>(msg, priority).await
+    }
+
+    /// like `call` but never waits for a free slot in a bounded mailbox
+    /// fails with `MailboxError::Full` if the mailbox is full
+    pub async fn try_call<T: Message, K>(&self, msg: T) -> anyhow::Result<T::Result>
+    where
+        A: Dispatch<T, K>,
+    {
+        self.try_call_handler::<A, T, K>(msg).await
+    }
+
+    /// Ok(None) means it is timeout
+    /// Ok(Some(res)) means it is not timeout
+    /// Err(e) means it is not timeout but with error occurred
+    pub async fn call_timeout<T: Message, K>(
+        &self,
+        msg: T,
+        timeout: Duration,
+    ) -> anyhow::Result<Option<T::Result>>
+    where
+        A: Dispatch<T, K>,
+    {
+        self.call_timeout_handler::<A, T, K>(msg, timeout).await
+    }
+
+    /// create a proxy (like delegate in C#)
+    /// the type of actor is not needed when you calling to proxy.
+    pub async fn proxy<T: Message>(&self) -> Proxy<T>
+    where
+        A: Handler<T>,
+    {
+        self.proxy_handler::<A, T, Shared>()
+    }
+
+    /// like `proxy` but for a message handled by `MutHandler`
+    pub async fn proxy_mut<T: Message>(&self) -> Proxy<T>
+    where
+        A: MutHandler<T>,
+    {
+        self.proxy_handler::<A, T, Exclusive>()
+    }
+}
+
+impl Addr {
+    /// force to block the unblocked call
+    /// waits for a free slot if the mailbox is bounded and full
+    pub async fn call<A: Handler<T>, T: Message>(&self, msg: T) -> anyhow::Result<T::Result> {
+        self.call_unblock_handler::<A, T, Shared>(msg, T::PRIORITY)
+            .await
+            .await?
+    }
+
+    /// unblocking call
+    /// you can await on the receiver to get the result when you need
+    pub async fn call_unblock<A: Handler<T>, T: Message>(
+        &self,
+        msg: T,
+    ) -> oneshot::Receiver<anyhow::Result<T::Result>> {
+        self.call_unblock_handler::<A, T, Shared>(msg, T::PRIORITY)
+            .await
+    }
+
+    /// fire and forget, the result of the handler is dropped
+    /// returns as soon as the message is in the mailbox
+    /// waits for a free slot if the mailbox is bounded and full
+    pub async fn send<A: Handler<T>, T: Message>(&self, msg: T) -> anyhow::Result<()> {
+        self.send_handler::<A, T, Shared>(msg, T::PRIORITY).await
+    }
+
+    /// like `call` but queued in the lane of `priority` instead of the one
+    /// declared by the message
+    pub async fn call_with_priority<A: Handler<T>, T: Message>(
+        &self,
+        msg: T,
+        priority: Priority,
+    ) -> anyhow::Result<T::Result> {
+        self.call_unblock_handler::<A, T, Shared>(msg, priority)
+            .await
+            .await?
+    }
+
+    /// like `send` but queued in the lane of `priority` instead of the one
+    /// declared by the message
+    pub async fn send_with_priority<A: Handler<T>, T: Message>(
+        &self,
+        msg: T,
+        priority: Priority,
+    ) -> anyhow::Result<()> {
+        self.send_handler::<A, T, Shared>(msg, priority).await
+    }
+
+    /// like `call` but never waits for a free slot in a bounded mailbox
+    /// fails with `MailboxError::Full` if the mailbox is full
+    pub async fn try_call<A: Handler<T>, T: Message>(&self, msg: T) -> anyhow::Result<T::Result> {
+        self.try_call_handler::<A, T, Shared>(msg).await
+    }
+
+    /// Ok(None) means it is timeout
+    /// Ok(Some(res)) means it is not timeout
+    /// Err(e) means it is not timeout but with error occurred
+    pub async fn call_timeout<A: Handler<T>, T: Message>(
+        &self,
+        msg: T,
+        timeout: Duration,
+    ) -> anyhow::Result<Option<T::Result>> {
+        self.call_timeout_handler::<A, T, Shared>(msg, timeout)
+            .await
+    }
+
+    /// create a proxy (like delegate in C#)
+    /// you only needs to care the lifetime and the type when you trying to
+    /// create when you calling to proxy, the type of actor is not needed.
+    pub async fn proxy<A: Handler<T>, T: Message>(&self) -> Proxy<T> {
+        self.proxy_handler::<A, T, Shared>()
+    }
+}
+
+impl<A> Clone for Addr<A> {
+    fn clone(&self) -> Self {
+        Addr::new(
+            self.id,
+            self.tx.clone(),
+            self.rx_exit.clone(),
+            self.system.clone(),
+        )
+    }
+}
+
+impl<A> std::fmt::Debug for Addr<A> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "<Addr: {}>", self.id)
     }
 }
 
-impl Hash for Addr {
+impl<A> Hash for Addr<A> {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         self.id.hash(state);
     }
@@ -249,13 +657,15 @@ impl Hash for Addr {
 
 /// weak version of Addr
 /// for avoid cyclic reference for Arc
-pub struct WeakAddr {
+pub struct WeakAddr<A = Untyped> {
     pub id: ActorID,
-    pub(crate) _tx: Weak<mpsc::UnboundedSender<Event>>,
-    pub(crate) _rx_exit: Shared<oneshot::Receiver<()>>,
+    pub(crate) _tx: Weak<MailboxSender>,
+    pub(crate) _rx_exit: ExitSignal,
+    pub(crate) _system: ActorSystem,
+    pub(crate) _marker: PhantomData<fn() -> A>,
 }
 
-impl WeakAddr {
+impl<A> WeakAddr<A> {
     pub fn get_name_or_id_string(&self) -> String {
         let name = self.get_name();
         if let Some(name) = name {
@@ -266,25 +676,56 @@ impl WeakAddr {
     }
 
     pub fn get_name(&self) -> Option<String> {
-        ACTOR_ID_NAME.get(&self.id)?.clone()
+        self._system.name(self.id)
+    }
+
+    /// wait to stop and get the exit reason
+    pub async fn await_stop(&self) -> ExitReason {
+        self._rx_exit.clone().await.unwrap_or(ExitReason::Killed)
+    }
+
+    /// the exit reason, None if it is still running
+    pub fn exit_reason(&self) -> Option<ExitReason> {
+        exit_reason(&self._rx_exit)
+    }
+
+    pub fn upgrade(&self) -> Option<Addr<A>> {
+        self._tx
+            .upgrade()
+            .map(|tx| Addr::new(self.id, tx, self._rx_exit.clone(), self._system.clone()))
     }
 
-    pub fn upgrade(&self) -> Option<Addr> {
-        self._tx.upgrade().map(|tx| Addr {
+    /// erase the type of the actor
+    pub fn erase(self) -> WeakAddr {
+        WeakAddr {
             id: self.id,
-            tx,
-            rx_exit: self._rx_exit.clone(),
-        })
+            _tx: self._tx,
+            _rx_exit: self._rx_exit,
+            _system: self._system,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<A> Clone for WeakAddr<A> {
+    fn clone(&self) -> Self {
+        WeakAddr {
+            id: self.id,
+            _tx: self._tx.clone(),
+            _rx_exit: self._rx_exit.clone(),
+            _system: self._system.clone(),
+            _marker: PhantomData,
+        }
     }
 }
 
-impl std::fmt::Debug for WeakAddr {
+impl<A> std::fmt::Debug for WeakAddr<A> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "<Addr?: {}>", self.id)
     }
 }
 
-impl Hash for WeakAddr {
+impl<A> Hash for WeakAddr<A> {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         self.id.hash(state);
     }
@@ -8,7 +8,7 @@
 
 use crate::actor::{
     addr::{Addr, WeakAddr},
-    message::{Handler, Message},
+    message::{Handler, Message, MutHandler},
     proxy::Proxy,
 };
 
@@ -35,10 +35,18 @@ pub struct Subscribe<T: Message + Sync + Clone> {
 }
 
 impl<T: Message + Sync + Clone> Subscribe<T> {
-    pub async fn from_addr<A: Handler<T>>(addr: &Addr) -> Self {
+    pub async fn from_addr<A: Handler<T>>(addr: &Addr<A>) -> Self {
         Self {
-            addr: addr.downgrade(),
-            proxy: addr.proxy::<A, T>().await,
+            addr: addr.downgrade().erase(),
+            proxy: addr.proxy().await,
+        }
+    }
+
+    /// like `from_addr` but for an actor handling `T` by `MutHandler`
+    pub async fn from_addr_mut<A: MutHandler<T>>(addr: &Addr<A>) -> Self {
+        Self {
+            addr: addr.downgrade().erase(),
+            proxy: addr.proxy_mut().await,
         }
     }
 }
@@ -1,52 +1,73 @@
 use std::{
     lazy::SyncOnceCell,
     sync::{Arc, Weak},
+    time::Duration,
 };
 
-use futures::{
-    channel::{mpsc, oneshot},
-    future::{join_all, Shared},
-    lock::Mutex,
-};
+use anyhow::Result;
+use futures::{future::join_all, lock::Mutex};
+use tracing::warn;
 
 use super::{
-    addr::{Event, WeakAddr},
+    addr::{Addr, Event, WeakAddr},
+    link::{spawn_monitor, ChildFailed},
+    mailbox::{self, MailboxCapacity, MailboxError, MailboxReceiver, MailboxSender},
+    message::{Dispatch, Handler, Message, Shared},
     proxy::Proxy,
-    runner::{ActorID, ACTOR_ID},
+    runner::{Actor, ActorID, ActorRunner, ExitSignal, StopMode},
+    stream::Streams,
     supervisor::Restart,
+    system::ActorSystem,
+    timer::Timers,
 };
 
 /// the context of an actor
 pub struct Context {
     pub id: ActorID,
-    tx: Weak<mpsc::UnboundedSender<Event>>,
-    pub(crate) rx_exit: Shared<oneshot::Receiver<()>>,
+    pub(crate) system: ActorSystem,
+    pub(crate) tx: Weak<MailboxSender>,
+    pub(crate) rx_exit: ExitSignal,
     pub(crate) supervisors: Mutex<Vec<Proxy<Restart>>>,
     pub(crate) addr: SyncOnceCell<WeakAddr>,
+    pub(crate) parent: SyncOnceCell<WeakAddr>,
+    /// in spawn order
+    children: std::sync::Mutex<Vec<Child>>,
+    pub(crate) timers: Arc<Timers>,
+    pub(crate) streams: Arc<Streams>,
+}
+
+/// a child spawned by `spawn_child`
+struct Child {
+    addr: Addr,
+    /// how long to wait for it to stop before aborting it
+    shutdown_timeout: Duration,
 }
 
 unsafe impl Send for Context {}
 unsafe impl Sync for Context {}
 
 impl Context {
-    pub fn new(
-        rx_exit: Shared<oneshot::Receiver<()>>,
-    ) -> (
-        Self,
-        mpsc::UnboundedReceiver<Event>,
-        Arc<mpsc::UnboundedSender<Event>>,
-    ) {
-        let id = ACTOR_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-        let (tx, rx) = mpsc::unbounded();
+    pub(crate) fn new(
+        system: ActorSystem,
+        rx_exit: ExitSignal,
+        capacity: MailboxCapacity,
+    ) -> (Self, MailboxReceiver, Arc<MailboxSender>) {
+        let id = system.next_id();
+        let (tx, rx) = mailbox::channel(capacity);
         let tx = Arc::new(tx);
         let weak_tx = Arc::downgrade(&tx);
         (
             Self {
                 id,
+                system,
                 tx: weak_tx,
                 rx_exit,
                 supervisors: Mutex::new(vec![]),
                 addr: SyncOnceCell::new(),
+                parent: SyncOnceCell::new(),
+                children: std::sync::Mutex::new(vec![]),
+                timers: Arc::new(Timers::default()),
+                streams: Arc::new(Streams::default()),
             },
             rx,
             tx,
@@ -54,10 +75,145 @@ impl Context {
     }
 
     /// to stop an actor
+    /// same as `stop_with_mode(StopMode::Immediate)`
     pub fn stop(&self) {
+        self.stop_with_mode(StopMode::Immediate);
+    }
+
+    /// to stop an actor, `mode` decides what happens to the queued messages
+    pub fn stop_with_mode(&self, mode: StopMode) {
         if let Some(tx) = self.tx.upgrade() {
-            tx.unbounded_send(Event::Stop(Ok(())))
-                .expect("failed to send stop event");
+            let _ = tx.send_system(Event::Stop(Ok(()), mode));
+        }
+    }
+
+    /// the system the actor belongs to
+    pub fn system(&self) -> &ActorSystem {
+        &self.system
+    }
+
+    /// the address of this actor
+    /// None if all the addresses are dropped and the actor is stopping
+    pub fn address(&self) -> Option<Addr> {
+        self.weak_address().upgrade()
+    }
+
+    /// the weak address of this actor
+    /// hand it out instead of `address` to avoid keeping the actor alive
+    pub fn weak_address(&self) -> WeakAddr {
+        self.addr.get().expect("addr is not set").clone()
+    }
+
+    /// enqueue `msg` to this actor without waiting for it to be handled
+    /// `A` is the type of this actor
+    /// unlike calling its own `Addr::call` from a handler, it never
+    /// deadlocks, the message is handled after the current handler
+    /// fails with `MailboxError::Full` if the mailbox is bounded and full
+    pub fn notify<A: Dispatch<T, K>, T: Message, K>(&self, msg: T) -> Result<()> {
+        self.tx
+            .upgrade()
+            .ok_or(MailboxError::Closed)?
+            .try_send(A::exec_event(msg, None), T::PRIORITY)?;
+        Ok(())
+    }
+
+    /// the actor which spawned this one by `spawn_child`
+    pub fn parent(&self) -> Option<WeakAddr> {
+        self.parent.get().cloned()
+    }
+
+    /// the running children in spawn order
+    pub fn children(&self) -> Vec<Addr> {
+        let mut children = self.children.lock().expect("children lock is poisoned");
+        children.retain(|child| child.addr.exit_reason().is_none());
+        children.iter().map(|child| child.addr.clone()).collect()
+    }
+
+    /// spawn an actor owned by this one
+    /// `P` is the type of this actor, it gets a `ChildFailed` message when the
+    /// child exits with a failure
+    /// the children are stopped in reverse spawn order before `on_stop` of
+    /// this actor, a child still running after its shutdown timeout is
+    /// aborted, see `ActorRunner::with_shutdown_timeout`
+    pub async fn spawn_child<P: Handler<ChildFailed>, C: Actor>(
+        &self,
+        child: C,
+    ) -> Result<Addr<C>> {
+        self.spawn_child_with::<P, C>(self.system.runner(), child)
+            .await
+    }
+
+    /// like `spawn_child` but runs the child with a custom runner
+    pub async fn spawn_child_with<P: Handler<ChildFailed>, C: Actor>(
+        &self,
+        runner: ActorRunner,
+        child: C,
+    ) -> Result<Addr<C>> {
+        let parent = self.weak_address();
+        let shutdown_timeout = runner.shutdown_timeout;
+        runner
+            .ctx
+            .parent
+            .set(parent)
+            .expect("parent is already set");
+        let addr = runner.run(child).await?;
+        let id = addr.id;
+        spawn_monitor(
+            addr.rx_exit.clone(),
+            self.rx_exit.clone(),
+            Proxy::from_mailbox(
+                self.id,
+                self.tx.clone(),
+                <P as Dispatch<ChildFailed, Shared>>::exec_event,
+            ),
+            move |reason| (!reason.is_normal()).then(|| ChildFailed { id, reason }),
+        );
+        let mut children = self.children.lock().expect("children lock is poisoned");
+        children.retain(|child| child.addr.exit_reason().is_none());
+        children.push(Child {
+            addr: addr.clone().erase(),
+            shutdown_timeout,
+        });
+        Ok(addr)
+    }
+
+    /// stop the children one by one in reverse spawn order
+    /// each one is aborted if it is still running after its shutdown timeout
+    pub(crate) async fn stop_children(&self) {
+        let children =
+            std::mem::take(&mut *self.children.lock().expect("children lock is poisoned"));
+        for Child {
+            addr,
+            shutdown_timeout,
+        } in children.into_iter().rev()
+        {
+            addr.clone().stop_with_mode(Ok(()), StopMode::Immediate);
+            if tokio::time::timeout(shutdown_timeout, addr.await_stop())
+                .await
+                .is_err()
+            {
+                // `on_stop` may be the one stuck
+                warn!(
+                    "{} is killed after {:?}",
+                    addr.get_name_or_id_string(),
+                    shutdown_timeout
+                );
+                self.system.abort_actor(addr.id);
+            }
+        }
+    }
+
+    /// escalate the failure by failing all the supervisors with it
+    pub(crate) async fn escalate(&self, err: &anyhow::Error) {
+        for supervisor in self.supervisors.lock().await.iter() {
+            let _ = supervisor.send_system(Event::Stop(
+                Err(anyhow::anyhow!(
+                    "escalated from actor {}: {:#}",
+                    self.id,
+                    err
+                )),
+                StopMode::Immediate,
+            ));
         }
     }
 
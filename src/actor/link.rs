@@ -0,0 +1,87 @@
+use std::sync::Weak;
+
+use super::{
+    addr::Event,
+    mailbox::MailboxSender,
+    message::Message,
+    proxy::Proxy,
+    runner::{ActorID, ExitReason, ExitSignal},
+};
+
+/// delivered to the watcher when a monitored actor exits
+/// see `Addr::monitor`
+#[derive(Debug, Clone)]
+pub struct Down {
+    pub id: ActorID,
+    pub reason: ExitReason,
+}
+
+impl Message for Down {
+    type Result = ();
+}
+
+/// delivered to an actor trapping exits when a linked actor exits
+/// see `Addr::link` and `ActorRunner::with_trap_exit`
+#[derive(Debug, Clone)]
+pub struct Exit {
+    pub id: ActorID,
+    pub reason: ExitReason,
+}
+
+impl Message for Exit {
+    type Result = ();
+}
+
+/// delivered to the parent when one of its children fails
+/// see `Context::spawn_child`
+#[derive(Debug, Clone)]
+pub struct ChildFailed {
+    pub id: ActorID,
+    pub reason: ExitReason,
+}
+
+impl Message for ChildFailed {
+    type Result = ();
+}
+
+/// one side of a link
+pub(crate) struct LinkEnd {
+    pub(crate) id: ActorID,
+    pub(crate) tx: Weak<MailboxSender>,
+    pub(crate) rx_exit: ExitSignal,
+}
+
+/// wait for the target to exit and send the message built from its exit
+/// reason to the watcher, nothing is sent if `msg` returns None
+/// gives up if the watcher exits first
+pub(crate) fn spawn_monitor<T: Message>(
+    target_exit: ExitSignal,
+    watcher_exit: ExitSignal,
+    watcher: Proxy<T>,
+    msg: impl FnOnce(ExitReason) -> Option<T> + Send + 'static,
+) {
+    tokio::task::spawn(async move {
+        tokio::select! {
+            reason = target_exit => {
+                if let Some(msg) = msg(reason.unwrap_or(ExitReason::Killed)) {
+                    let _ = watcher.send(msg).await;
+                }
+            }
+            _ = watcher_exit => {}
+        }
+    });
+}
+
+/// wait for either side to exit and send the exit reason to the other side
+pub(crate) fn spawn_link(a: LinkEnd, b: LinkEnd) {
+    tokio::task::spawn(async move {
+        let (id, reason, tx) = tokio::select! {
+            reason = a.rx_exit => (a.id, reason, b.tx),
+            reason = b.rx_exit => (b.id, reason, a.tx),
+        };
+        let reason = reason.unwrap_or(ExitReason::Killed);
+        if let Some(tx) = tx.upgrade() {
+            let _ = tx.send_system(Event::LinkExit(id, reason));
+        }
+    });
+}
@@ -0,0 +1,201 @@
+use std::sync::Arc;
+
+use tokio::sync::{mpsc, OwnedSemaphorePermit, Semaphore};
+
+use super::addr::Event;
+
+/// capacity of an actor's mailbox
+/// chosen when the actor is spawned by `ActorRunner::with_capacity`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MailboxCapacity {
+    /// the mailbox grows as needed and senders never wait
+    Unbounded,
+    /// at most `n` messages are queued, whatever their priority, senders wait
+    /// until a slot is free
+    Bounded(usize),
+}
+
+impl Default for MailboxCapacity {
+    fn default() -> Self {
+        MailboxCapacity::Unbounded
+    }
+}
+
+/// error when an event could not be put into the mailbox
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MailboxError {
+    /// the mailbox is bounded and has no free slot
+    Full,
+    /// the actor is stopped and the mailbox is closed
+    Closed,
+}
+
+impl std::fmt::Display for MailboxError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MailboxError::Full => write!(f, "error: mailbox is full"),
+            MailboxError::Closed => write!(f, "error: mailbox is closed"),
+        }
+    }
+}
+
+impl std::error::Error for MailboxError {}
+
+/// lane of the mailbox a message is queued in
+/// system events (stop, restart...) always go first, then `High`, then
+/// `Normal`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    Normal,
+    High,
+}
+
+impl Default for Priority {
+    fn default() -> Self {
+        Priority::Normal
+    }
+}
+
+/// sending half of the mailbox
+pub(crate) struct MailboxSender {
+    system: mpsc::UnboundedSender<Event>,
+    high: mpsc::UnboundedSender<Queued>,
+    normal: mpsc::UnboundedSender<Queued>,
+    /// the free slots of a bounded mailbox, shared by the message lanes
+    slots: Option<Arc<Semaphore>>,
+}
+
+/// receiving half of the mailbox, owned by the runner
+pub(crate) struct MailboxReceiver {
+    system: mpsc::UnboundedReceiver<Event>,
+    high: mpsc::UnboundedReceiver<Queued>,
+    normal: mpsc::UnboundedReceiver<Queued>,
+    slots: Option<Arc<Semaphore>>,
+}
+
+/// a queued message, its slot is freed once it is received
+struct Queued {
+    event: Event,
+    _slot: Option<OwnedSemaphorePermit>,
+}
+
+/// the system lane is always unbounded, `capacity` applies to the messages of
+/// both message lanes together
+pub(crate) fn channel(capacity: MailboxCapacity) -> (MailboxSender, MailboxReceiver) {
+    let slots = match capacity {
+        MailboxCapacity::Unbounded => None,
+        MailboxCapacity::Bounded(n) => {
+            assert!(n > 0, "bounded mailbox capacity must be greater than 0");
+            Some(Arc::new(Semaphore::new(n)))
+        }
+    };
+    let (system_tx, system_rx) = mpsc::unbounded_channel();
+    let (high_tx, high_rx) = mpsc::unbounded_channel();
+    let (normal_tx, normal_rx) = mpsc::unbounded_channel();
+    (
+        MailboxSender {
+            system: system_tx,
+            high: high_tx,
+            normal: normal_tx,
+            slots: slots.clone(),
+        },
+        MailboxReceiver {
+            system: system_rx,
+            high: high_rx,
+            normal: normal_rx,
+            slots,
+        },
+    )
+}
+
+impl MailboxSender {
+    fn enqueue(
+        &self,
+        event: Event,
+        priority: Priority,
+        slot: Option<OwnedSemaphorePermit>,
+    ) -> Result<(), MailboxError> {
+        let lane = match priority {
+            Priority::Normal => &self.normal,
+            Priority::High => &self.high,
+        };
+        lane.send(Queued { event, _slot: slot })
+            .map_err(|_| MailboxError::Closed)
+    }
+
+    /// put an event into the lane of `priority`
+    /// waits for a free slot if the mailbox is bounded and full
+    pub(crate) async fn send(&self, event: Event, priority: Priority) -> Result<(), MailboxError> {
+        let slot = match &self.slots {
+            Some(slots) => Some(
+                slots
+                    .clone()
+                    .acquire_owned()
+                    .await
+                    .map_err(|_| MailboxError::Closed)?,
+            ),
+            None => None,
+        };
+        self.enqueue(event, priority, slot)
+    }
+
+    /// put an event into the lane of `priority` without waiting
+    pub(crate) fn try_send(&self, event: Event, priority: Priority) -> Result<(), MailboxError> {
+        let slot = match &self.slots {
+            Some(slots) => Some(slots.clone().try_acquire_owned().map_err(|e| match e {
+                tokio::sync::TryAcquireError::NoPermits => MailboxError::Full,
+                tokio::sync::TryAcquireError::Closed => MailboxError::Closed,
+            })?),
+            None => None,
+        };
+        self.enqueue(event, priority, slot)
+    }
+
+    /// put an event into the lane of `priority` even if the mailbox is full
+    pub(crate) fn force_send(&self, event: Event, priority: Priority) -> Result<(), MailboxError> {
+        self.enqueue(event, priority, None)
+    }
+
+    /// put a system event (stop, restart...) into the system lane
+    /// it never waits and overtakes all the queued messages
+    pub(crate) fn send_system(&self, event: Event) -> Result<(), MailboxError> {
+        self.system.send(event).map_err(|_| MailboxError::Closed)
+    }
+}
+
+impl MailboxReceiver {
+    /// the next event by priority
+    /// only system events are received if `messages` is false
+    pub(crate) async fn recv(&mut self, messages: bool) -> Option<Event> {
+        tokio::select! {
+            biased;
+            Some(event) = self.system.recv() => Some(event),
+            Some(queued) = self.high.recv(), if messages => Some(queued.event),
+            Some(queued) = self.normal.recv(), if messages => Some(queued.event),
+            else => match messages {
+                true => None,
+                false => futures::future::pending().await,
+            },
+        }
+    }
+
+    /// the next queued event by priority without waiting
+    pub(crate) fn try_recv(&mut self) -> Option<Event> {
+        self.system
+            .try_recv()
+            .ok()
+            .or_else(|| self.high.try_recv().ok().map(|queued| queued.event))
+            .or_else(|| self.normal.try_recv().ok().map(|queued| queued.event))
+    }
+
+    /// refuse new events and drop the queued ones
+    pub(crate) fn close(&mut self) {
+        if let Some(slots) = &self.slots {
+            slots.close();
+        }
+        self.system.close();
+        self.high.close();
+        self.normal.close();
+        while self.try_recv().is_some() {}
+    }
+}
@@ -1,13 +1,23 @@
 use std::sync::Arc;
 
 use anyhow::Result;
+use futures::channel::oneshot;
 
-use super::{context::Context, runner::Actor};
+use super::{
+    addr::{handler_exec, mut_handler_exec, Event},
+    context::Context,
+    mailbox::Priority,
+    runner::{Actor, ErrorPolicy},
+};
 
 /// message trait
 /// like args in function call
 pub trait Message: 'static + Send {
     type Result: 'static + Send;
+    /// overrides the error policy of the actor when handling this message
+    const ERROR_POLICY: Option<ErrorPolicy> = None;
+    /// the lane of the mailbox this message is queued in
+    const PRIORITY: Priority = Priority::Normal;
 }
 
 /// handle message for actor
@@ -29,3 +39,43 @@ impl<T: Message> Handler<T> for Arc<dyn Handler<T>> {
 /// for handling message
 #[async_trait::async_trait]
 impl<T: Message> Actor for Arc<dyn Handler<T>> {}
+
+/// handle message for actor with exclusive access to its state
+/// the runner waits for the other handlers in flight before calling it, so no
+/// `Mutex` or atomics are needed to change the actor.
+/// the messages are sent through a typed `Addr<A>` like the ones of
+/// `Handler`, see `Addr::proxy_mut` for a proxy.
+#[async_trait::async_trait]
+pub trait MutHandler<T: Message>: Actor {
+    async fn handle(&mut self, ctx: &Context, msg: T) -> Result<T::Result>;
+}
+
+/// how a message reaches its handler, implemented by xtor for the actors
+/// implementing `Handler<T>` (`K` is `Shared`) or `MutHandler<T>` (`K` is
+/// `Exclusive`)
+/// `K` is inferred, do not implement it
+#[doc(hidden)]
+pub trait Dispatch<T: Message, K>: Actor {
+    /// the event to handle `msg` in the runner
+    fn exec_event(msg: T, tx: Option<oneshot::Sender<Result<T::Result>>>) -> Event;
+}
+
+/// `Dispatch` through `Handler`
+#[doc(hidden)]
+pub enum Shared {}
+
+/// `Dispatch` through `MutHandler`
+#[doc(hidden)]
+pub enum Exclusive {}
+
+impl<A: Handler<T>, T: Message> Dispatch<T, Shared> for A {
+    fn exec_event(msg: T, tx: Option<oneshot::Sender<Result<T::Result>>>) -> Event {
+        Event::Exec(handler_exec::<A, T>(msg, tx))
+    }
+}
+
+impl<A: MutHandler<T>, T: Message> Dispatch<T, Exclusive> for A {
+    fn exec_event(msg: T, tx: Option<oneshot::Sender<Result<T::Result>>>) -> Event {
+        Event::ExecMut(mut_handler_exec::<A, T>(msg, tx))
+    }
+}
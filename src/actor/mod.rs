@@ -4,18 +4,36 @@ pub mod addr;
 pub mod broker;
 /// context of the actor
 pub mod context;
+/// links and monitors between actors
+pub mod link;
+/// mailbox of the actor
+pub mod mailbox;
 /// message of the actor
 pub mod message;
 /// message handler's proxy
 pub mod proxy;
+/// name registry of the actors
+pub mod registry;
 /// actor runner
 pub mod runner;
+/// streams attached to the actor
+pub mod stream;
 /// supervisor
 pub mod supervisor;
+/// actor system
+pub mod system;
+/// timers of the actor
+pub mod timer;
 
 pub use addr::*;
 pub use context::*;
+pub use link::*;
+pub use mailbox::*;
 pub use message::*;
 pub use proxy::*;
+pub use registry::*;
 pub use runner::*;
+pub use stream::*;
 pub use supervisor::*;
+pub use system::*;
+pub use timer::*;
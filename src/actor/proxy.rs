@@ -1,9 +1,14 @@
-use std::pin::Pin;
+use std::{pin::Pin, sync::Weak};
 
 use anyhow::Result;
 use futures::{channel::oneshot, Future};
 
-use super::{message::Message, runner::ActorID};
+use super::{
+    addr::Event,
+    mailbox::{MailboxError, MailboxSender, Priority},
+    message::Message,
+    runner::ActorID,
+};
 
 pub(crate) type ProxyRetBlock<T> = Pin<
     Box<
@@ -13,21 +18,94 @@ pub(crate) type ProxyRetBlock<T> = Pin<
     >,
 >;
 pub(crate) type ProxyFnBlock<T> = Box<dyn Fn(T) -> ProxyRetBlock<T> + Send + Sync + 'static>;
+pub(crate) type ProxyExecFn<T> =
+    fn(T, Option<oneshot::Sender<Result<<T as Message>::Result>>>) -> Event;
 
 /// proxy for actor's message handler
 /// like C#'s delegate
 pub struct Proxy<T: Message> {
     pub id: ActorID,
     pub proxy_inner: ProxyFnBlock<T>,
+    /// the mailbox of the actor, None for a proxy built by `Proxy::new`
+    mailbox: Option<(Weak<MailboxSender>, ProxyExecFn<T>)>,
 }
 
 impl<T: Message> Proxy<T> {
+    /// a proxy calling `proxy_inner` for each message
+    /// it has no mailbox, so the priority is ignored and `try_call` may wait
     pub fn new(id: ActorID, proxy_inner: ProxyFnBlock<T>) -> Self {
-        Self { id, proxy_inner }
+        Self {
+            id,
+            proxy_inner,
+            mailbox: None,
+        }
+    }
+
+    /// a proxy putting the messages into the mailbox `tx`
+    pub(crate) fn from_mailbox(id: ActorID, tx: Weak<MailboxSender>, exec: ProxyExecFn<T>) -> Self {
+        let weak_tx = tx.clone();
+        Self {
+            id,
+            proxy_inner: Box::new(move |msg| {
+                Self::enqueue(weak_tx.clone(), exec, msg, T::PRIORITY)
+            }),
+            mailbox: Some((tx, exec)),
+        }
+    }
+
+    /// send a system event without waiting
+    pub(crate) fn send_system(&self, event: Event) -> std::result::Result<(), MailboxError> {
+        self.mailbox
+            .as_ref()
+            .and_then(|(tx, _)| tx.upgrade())
+            .ok_or(MailboxError::Closed)?
+            .send_system(event)
     }
 
+    /// waits for a free slot if the mailbox is bounded and full
     pub async fn call(&self, msg: T) -> Result<T::Result> {
-        (self.proxy_inner)(msg).await?.await?
+        self.call_with_priority(msg, T::PRIORITY).await
+    }
+
+    /// fire and forget, the result of the handler is dropped
+    /// returns as soon as the message is in the mailbox
+    pub async fn send(&self, msg: T) -> Result<()> {
+        self.send_with_priority(msg, T::PRIORITY).await
+    }
+
+    /// like `call` but queued in the lane of `priority` instead of the one
+    /// declared by the message
+    pub async fn call_with_priority(&self, msg: T, priority: Priority) -> Result<T::Result> {
+        self.call_unblock_priority(msg, priority).await?.await?
+    }
+
+    /// like `send` but queued in the lane of `priority` instead of the one
+    /// declared by the message
+    pub async fn send_with_priority(&self, msg: T, priority: Priority) -> Result<()> {
+        match &self.mailbox {
+            Some((tx, exec)) => {
+                tx.upgrade()
+                    .ok_or_else(|| anyhow::anyhow!("error: proxy tx is dropped"))?
+                    .send(exec(msg, None), priority)
+                    .await?
+            }
+            None => drop((self.proxy_inner)(msg).await?),
+        }
+        Ok(())
+    }
+
+    /// like `call` but never waits for a free slot in a bounded mailbox
+    /// fails with `MailboxError::Full` if the mailbox is full
+    pub async fn try_call(&self, msg: T) -> Result<T::Result> {
+        let (tx, exec) = match &self.mailbox {
+            Some(mailbox) => mailbox,
+            None => return self.call(msg).await,
+        };
+        let (reply_tx, rx) = oneshot::channel();
+        tx.upgrade()
+            .ok_or_else(|| anyhow::anyhow!("error: proxy tx is dropped"))?
+            .try_send(exec(msg, Some(reply_tx)), T::PRIORITY)?;
+        rx.await?
     }
 
     pub async fn call_timeout(
@@ -36,7 +114,7 @@ impl<T: Message> Proxy<T> {
         timeout: std::time::Duration,
     ) -> Result<Option<T::Result>> {
         tokio::select! {
-            res =( self.proxy_inner)(msg).await? => {
+            res = self.call_unblock(msg).await.await? => {
                 res?.map( Some )
             }
             _ = tokio::time::sleep(timeout) => Ok(None)
@@ -46,4 +124,27 @@ impl<T: Message> Proxy<T> {
     pub async fn call_unblock(&self, msg: T) -> ProxyRetBlock<T> {
         (self.proxy_inner)(msg)
     }
+
+    fn call_unblock_priority(&self, msg: T, priority: Priority) -> ProxyRetBlock<T> {
+        match &self.mailbox {
+            Some((tx, exec)) => Self::enqueue(tx.clone(), *exec, msg, priority),
+            None => (self.proxy_inner)(msg),
+        }
+    }
+
+    fn enqueue(
+        tx: Weak<MailboxSender>,
+        exec: ProxyExecFn<T>,
+        msg: T,
+        priority: Priority,
+    ) -> ProxyRetBlock<T> {
+        Box::pin(async move {
+            let (reply_tx, rx) = oneshot::channel();
+            tx.upgrade()
+                .ok_or_else(|| anyhow::anyhow!("error: proxy tx is dropped"))?
+                .send(exec(msg, Some(reply_tx)), priority)
+                .await?;
+            Ok(rx)
+        })
+    }
 }
@@ -0,0 +1,142 @@
+use std::any::TypeId;
+
+use dashmap::mapref::entry::Entry;
+
+use super::{
+    addr::{Addr, Untyped, WeakAddr},
+    runner::{Actor, ActorID},
+    system::ActorSystem,
+};
+
+/// an actor in the name registry
+pub struct Registered {
+    type_id: TypeId,
+    addr: WeakAddr,
+}
+
+impl Registered {
+    fn is_alive(&self) -> bool {
+        self.addr.exit_reason().is_none()
+    }
+}
+
+/// error of `register`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegistryError {
+    /// another running actor is registered with the name
+    NameTaken(String),
+    /// the actor is already registered with another name
+    AlreadyRegistered(String),
+}
+
+impl std::fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RegistryError::NameTaken(name) => {
+                write!(f, "error: name {} is already registered", name)
+            }
+            RegistryError::AlreadyRegistered(name) => {
+                write!(f, "error: actor is already registered as {}", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RegistryError {}
+
+/// register an actor by name in its system, the name is also used as its
+/// display name
+/// the actor is unregistered when it exits
+pub fn register<A: Actor>(name: impl Into<String>, addr: &Addr<A>) -> Result<(), RegistryError> {
+    let system = &addr.system;
+    let name = name.into();
+    // held until the actor is in the registry, so concurrent registrations of
+    // the same actor are checked one by one
+    let registered_name = system.registered().entry(addr.id);
+    if let Entry::Occupied(entry) = &registered_name {
+        if system.is_registered(entry.get(), addr.id) {
+            return Err(RegistryError::AlreadyRegistered(entry.get().clone()));
+        }
+    }
+    let registered = Registered {
+        type_id: TypeId::of::<A>(),
+        addr: addr.downgrade().erase(),
+    };
+    match system.registry().entry(name.clone()) {
+        Entry::Occupied(entry) if entry.get().is_alive() => {
+            return Err(RegistryError::NameTaken(name));
+        }
+        Entry::Occupied(mut entry) => {
+            entry.insert(registered);
+        }
+        Entry::Vacant(entry) => {
+            entry.insert(registered);
+        }
+    }
+    match registered_name {
+        Entry::Occupied(mut entry) => {
+            entry.insert(name.clone());
+        }
+        Entry::Vacant(entry) => {
+            entry.insert(name.clone());
+        }
+    }
+    system.names().insert(addr.id, Some(name));
+    Ok(())
+}
+
+/// `ActorSystem::unregister` of the default system
+pub fn unregister(name: &str) -> bool {
+    ActorSystem::global().unregister(name)
+}
+
+/// `ActorSystem::whereis` of the default system
+pub fn whereis<A: 'static>(name: &str) -> Option<Addr<A>> {
+    ActorSystem::global().whereis(name)
+}
+
+impl ActorSystem {
+    /// remove a name from the registry
+    /// returns false if no actor is registered with the name
+    pub fn unregister(&self, name: &str) -> bool {
+        match self.registry().remove(name) {
+            Some((_, registered)) => {
+                self.registered()
+                    .remove_if(&registered.addr.id, |_, registered| registered == name);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// look up a running actor by name
+    /// `whereis::<A>(name)` only finds it if it is an `A`, the untyped
+    /// `whereis::<Untyped>(name)` finds any actor
+    pub fn whereis<A: 'static>(&self, name: &str) -> Option<Addr<A>> {
+        let registered = self.registry().get(name)?;
+        if TypeId::of::<A>() != TypeId::of::<Untyped>() && TypeId::of::<A>() != registered.type_id {
+            return None;
+        }
+        let addr = registered.addr.upgrade()?;
+        if addr.exit_reason().is_some() {
+            return None;
+        }
+        Some(Addr::new(addr.id, addr.tx, addr.rx_exit, addr.system))
+    }
+
+    /// whether the running actor `id` is registered as `name`
+    fn is_registered(&self, name: &str, id: ActorID) -> bool {
+        self.registry().get(name).map_or(false, |registered| {
+            registered.addr.id == id && registered.is_alive()
+        })
+    }
+
+    /// forget the names of an exited actor
+    pub(crate) fn remove_name(&self, id: ActorID) {
+        self.names().remove(&id);
+        if let Some((_, name)) = self.registered().remove(&id) {
+            self.registry()
+                .remove_if(&name, |_, registered| registered.addr.id == id);
+        }
+    }
+}
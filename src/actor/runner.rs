@@ -1,33 +1,142 @@
-use std::{
-    lazy::SyncLazy,
-    sync::{atomic::AtomicU64, Arc},
-};
+use std::{any::Any, collections::VecDeque, sync::Arc, time::Duration};
 
 use anyhow::Result;
 use dashmap::DashMap;
 use futures::{
-    channel::{
-        mpsc::{self, UnboundedSender},
-        oneshot,
-    },
-    FutureExt, StreamExt,
+    channel::oneshot, future::Shared, stream::FuturesUnordered, Future, FutureExt, StreamExt,
 };
-use tokio::task::JoinHandle;
+use tokio::time::Instant;
 use tracing::{info, warn};
 
 use super::{
-    addr::{Addr, Event, WeakAddr},
+    addr::{Addr, Event, ExecError, WeakAddr},
     context::Context,
+    link::Exit,
+    mailbox::{MailboxCapacity, MailboxReceiver, MailboxSender},
+    message::{self, Dispatch, Handler},
+    proxy::ProxyExecFn,
+    supervisor::HandlerPanic,
+    system::{ActorSystem, GlobalHandles, GlobalShim},
 };
 
-pub(crate) static ACTOR_ID: AtomicU64 = AtomicU64::new(0);
+/// the names of the actors of the default system
+#[deprecated(note = "use `Addr::get_name`")]
+pub static ACTOR_ID_NAME: GlobalShim<DashMap<ActorID, Option<String>>> =
+    GlobalShim(ActorSystem::names);
 
-pub static ACTOR_ID_NAME: SyncLazy<DashMap<u64, Option<String>>> = SyncLazy::new(DashMap::new);
-pub static ACTOR_ID_HANDLE: SyncLazy<DashMap<u64, JoinHandle<Result<()>>>> =
-    SyncLazy::new(DashMap::new);
+/// the running actors of the default system, only `is_empty`, `len` and
+/// `contains_key` are left
+#[deprecated(note = "use `ActorSystem::actor_count` or `ActorSystem::is_running`")]
+pub static ACTOR_ID_HANDLE: GlobalHandles = GlobalHandles(());
 
 pub type ActorID = u64;
 
+/// builds a new instance of a supervised actor for each restart
+pub type ActorFactory<A> = Box<dyn Fn() -> A + Send + Sync>;
+
+/// what the runner does when a handler returns an error
+/// the caller always receives the error
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorPolicy {
+    /// only reply the error to the caller and keep running
+    ReplyOnly,
+    /// stop the actor, default of `ActorRunner::run`
+    Stop,
+    /// hand off to the supervisors to restart the actor, default of
+    /// `ActorRunner::supervised_run`, stops the actor if it has no supervisor
+    Restart,
+    /// stop the actor and fail its supervisors with the error
+    Escalate,
+}
+
+/// how an actor stops
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopMode {
+    /// stop after the handlers in flight, the queued messages are dropped and
+    /// their callers get `ActorStopped`
+    Immediate,
+    /// handle all the queued messages first
+    Drain,
+    /// like `Drain`, but when the deadline is reached the actor is killed:
+    /// the handlers in flight and the queued messages are dropped
+    DrainWithDeadline(Duration),
+}
+
+impl Default for StopMode {
+    fn default() -> Self {
+        StopMode::Immediate
+    }
+}
+
+/// error of a message dropped because the actor is stopped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActorStopped;
+
+impl std::fmt::Display for ActorStopped {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "error: actor is stopped")
+    }
+}
+
+impl std::error::Error for ActorStopped {}
+
+/// why an actor exited
+/// published to every `Addr` and `WeakAddr` by `await_stop` and `exit_reason`
+#[derive(Debug, Clone)]
+pub enum ExitReason {
+    /// stopped with `Ok` or all the addresses are dropped
+    Normal,
+    /// stopped with an error
+    Stopped(Arc<anyhow::Error>),
+    /// a handler failed and the error policy stopped the actor
+    HandlerError(Arc<anyhow::Error>),
+    /// a handler panicked, needs the `supervisor_catch_unwind` feature
+    Panicked(String),
+    /// killed by `kill`, a drain deadline or `force_stop`
+    Killed,
+    /// the supervisors failed to restart the actor
+    SupervisorGaveUp(Arc<anyhow::Error>),
+    /// a linked actor failed
+    Linked(ActorID, Box<ExitReason>),
+}
+
+impl ExitReason {
+    pub fn is_normal(&self) -> bool {
+        matches!(self, ExitReason::Normal)
+    }
+
+    fn from_handler_error(err: anyhow::Error) -> Self {
+        match err.downcast_ref::<HandlerPanic>() {
+            Some(panic) => ExitReason::Panicked(panic.0.clone()),
+            None => ExitReason::HandlerError(Arc::new(err)),
+        }
+    }
+
+    fn into_result(self) -> Result<()> {
+        match self {
+            ExitReason::Normal => Ok(()),
+            reason => Err(anyhow::anyhow!("{}", reason)),
+        }
+    }
+}
+
+impl std::fmt::Display for ExitReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExitReason::Normal => write!(f, "normal"),
+            ExitReason::Stopped(err) => write!(f, "stopped: {:#}", err),
+            ExitReason::HandlerError(err) => write!(f, "handler error: {:#}", err),
+            ExitReason::Panicked(msg) => write!(f, "panicked: {}", msg),
+            ExitReason::Killed => write!(f, "killed"),
+            ExitReason::SupervisorGaveUp(err) => write!(f, "supervisor gave up: {:#}", err),
+            ExitReason::Linked(id, reason) => write!(f, "linked actor {} exited: {}", id, reason),
+        }
+    }
+}
+
+/// resolved with the exit reason once the actor is stopped
+pub(crate) type ExitSignal = Shared<oneshot::Receiver<ExitReason>>;
+
 #[async_trait::async_trait]
 pub trait Actor: Send + Sync + 'static {
     /// hook for actor initialization
@@ -49,7 +158,7 @@ pub trait Actor: Send + Sync + 'static {
     }
     /// check the name of the actor
     fn get_name(&self, ctx: &Context) -> Option<String> {
-        ACTOR_ID_NAME.get(&ctx.id)?.clone()
+        ctx.system.name(ctx.id)
     }
     fn get_name_or_id_string(&self, ctx: &Context) -> String {
         let name = self.get_name(ctx);
@@ -62,27 +171,42 @@ pub trait Actor: Send + Sync + 'static {
     /// starting an actor
     /// if you want a supervised actor you need to send message to supervisor
     /// instead starting it from here `?Sized` actor is not supported
-    async fn spawn(self) -> Result<Addr>
+    async fn spawn(self) -> Result<Addr<Self>>
     where
         Self: Sized,
     {
         ActorRunner::new().run(self).await
     }
 
-    async fn spawn_supervisable(self) -> Result<Addr>
+    async fn spawn_supervisable(self) -> Result<Addr<Self>>
     where
         Self: Sized + ActorRestart,
     {
         let addr = ActorRunner::new().supervised_run(self).await?;
         Ok(addr)
     }
+
+    /// like `spawn_supervisable`, but each restart builds a new instance by
+    /// `factory`, see `ActorRunner::supervised_run_with`
+    async fn spawn_supervised_with<F>(factory: F) -> Result<Addr<Self>>
+    where
+        Self: Sized + ActorRestart,
+        F: Fn() -> Self + Send + Sync + 'static,
+    {
+        ActorRunner::new().supervised_run_with(factory).await
+    }
 }
 
 pub struct ActorRunner {
     pub ctx: Context,
-    tx: Arc<UnboundedSender<Event>>,
-    rx: mpsc::UnboundedReceiver<Event>,
-    tx_exit: oneshot::Sender<()>,
+    tx: Arc<MailboxSender>,
+    rx: MailboxReceiver,
+    tx_exit: oneshot::Sender<ExitReason>,
+    error_policy: Option<ErrorPolicy>,
+    concurrency: usize,
+    trap_exit: Option<ProxyExecFn<Exit>>,
+    restart_lifecycle: bool,
+    pub(crate) shutdown_timeout: Duration,
 }
 
 impl Default for ActorRunner {
@@ -92,133 +216,527 @@ impl Default for ActorRunner {
 }
 
 impl ActorRunner {
+    /// runner with an unbounded mailbox in the default system
     pub fn new() -> Self {
-        let (tx_exit, rx_exit) = oneshot::channel::<()>();
+        Self::with_capacity(MailboxCapacity::Unbounded)
+    }
+
+    /// runner with the given mailbox capacity in the default system
+    /// a bounded mailbox makes senders wait for a free slot (backpressure)
+    ///
+    /// # Panics
+    ///
+    /// panics if the capacity is `MailboxCapacity::Bounded(0)`
+    pub fn with_capacity(capacity: MailboxCapacity) -> Self {
+        Self::in_system(ActorSystem::global(), capacity)
+    }
+
+    pub(crate) fn in_system(system: ActorSystem, capacity: MailboxCapacity) -> Self {
+        let (tx_exit, rx_exit) = oneshot::channel();
         let rx_exit = rx_exit.shared();
-        let (ctx, rx, tx) = Context::new(rx_exit);
+        let (ctx, rx, tx) = Context::new(system, rx_exit, capacity);
         Self {
             ctx,
             tx,
             rx,
             tx_exit,
+            error_policy: None,
+            concurrency: 1,
+            trap_exit: None,
+            restart_lifecycle: false,
+            shutdown_timeout: Duration::from_secs(5),
         }
     }
 
-    pub async fn run<A: Actor>(self, actor: A) -> Result<Addr> {
+    /// set the error policy of the actor
+    /// a message could override it by `Message::ERROR_POLICY`
+    pub fn with_error_policy(mut self, policy: ErrorPolicy) -> Self {
+        self.error_policy = Some(policy);
+        self
+    }
+
+    /// set the max number of handlers running at the same time
+    /// default is 1, which handles messages one by one
+    /// stop and restart still wait for the handlers in flight
+    ///
+    /// # Panics
+    ///
+    /// panics if `n` is 0
+    pub fn with_concurrency(mut self, n: usize) -> Self {
+        assert!(n > 0, "concurrency must be greater than 0");
+        self.concurrency = n;
+        self
+    }
+
+    /// receive the exits of the linked actors as `Exit` messages instead of
+    /// being stopped by their failures
+    /// `A` must be the actor passed to `run` or `supervised_run`
+    pub fn with_trap_exit<A: Handler<Exit>>(mut self) -> Self {
+        self.trap_exit = Some(<A as Dispatch<Exit, message::Shared>>::exec_event);
+        self
+    }
+
+    /// call `on_stop` before and `on_start` after each restart, between
+    /// `ActorRestart::pre_restart` and `ActorRestart::post_restart`
+    /// only used by `supervised_run`
+    pub fn with_restart_lifecycle(mut self) -> Self {
+        self.restart_lifecycle = true;
+        self
+    }
+
+    /// how long the parent waits for this actor to stop before aborting it,
+    /// default to 5s
+    /// only used by `Context::spawn_child_with`
+    pub fn with_shutdown_timeout(mut self, timeout: Duration) -> Self {
+        self.shutdown_timeout = timeout;
+        self
+    }
+
+    /// run an actor
+    /// `ErrorPolicy::Restart` and `ErrorPolicy::Escalate` stop it because it
+    /// has no supervisor
+    pub async fn run<A: Actor>(self, actor: A) -> Result<Addr<A>> {
         let Self {
             ctx,
             mut rx,
             tx,
             tx_exit,
+            error_policy,
+            concurrency,
+            trap_exit,
+            ..
         } = self;
 
         let rx_exit = ctx.rx_exit.clone();
         let id = ctx.id;
-        ACTOR_ID_NAME.insert(id, None);
-        let actor = Arc::new(actor);
-        let addr = Addr { id, tx, rx_exit };
-        ctx.addr.set(addr.downgrade()).expect("addr is already set");
-        actor.on_start(&ctx).await?;
-        let handle = tokio::task::spawn(async move {
-            let mut exit_err = Ok(());
-            while let Some(event) = rx.next().await {
-                match event {
-                    Event::Stop(err) => {
-                        exit_err = err;
-                        break;
-                    }
-                    Event::Exec(f) => match f(actor.clone(), &ctx).await {
-                        Ok(_) => {}
-                        Err(err) => {
-                            exit_err = Err(err);
-                            break;
-                        }
-                    },
-                    Event::Restart => {
-                        panic!("this event could only send by supervisor");
-                    }
-                    Event::AddSupervisor(_) => {
-                        panic!("this event could only send by supervisor");
-                    }
-                }
-            }
+        let system = ctx.system.clone();
+        system.names().insert(id, None);
+        let mut actor = Arc::new(actor);
+        let addr = Addr::new(id, tx, rx_exit, system.clone());
+        ctx.addr
+            .set(addr.downgrade().erase())
+            .expect("addr is already set");
+        if let Err(err) = system.scope(actor.on_start(&ctx)).await {
+            // the actor never ran, forget the names it may have set
+            system.remove_name(id);
+            return Err(err);
+        }
+        system.add_actor(&ctx);
+        let error_policy = error_policy.unwrap_or(ErrorPolicy::Stop);
+        let handle = tokio::task::spawn(system.scope(async move {
+            let reason = Self::event_loop(
+                &mut actor,
+                &ctx,
+                &mut rx,
+                error_policy,
+                concurrency,
+                trap_exit,
+                false,
+            )
+            .await
+            .into_reason();
+            ctx.timers.cancel_all();
+            ctx.streams.cancel_all();
+            rx.close();
+            ctx.stop_children().await;
             actor.on_stop(&ctx).await;
-            tx_exit.send(()).expect("tx_exit is already closed");
-            ACTOR_ID_HANDLE.remove(&id);
-            exit_err
-        });
-        ACTOR_ID_HANDLE.insert(id, handle);
+            ctx.system.actor_exited(id);
+            tx_exit
+                .send(reason.clone())
+                .expect("tx_exit is already closed");
+            reason.into_result()
+        }));
+        system.set_handle(id, handle);
         Ok(addr)
     }
 
-    pub(crate) async fn supervised_run<A: Actor + ActorRestart>(self, actor: A) -> Result<Addr> {
+    /// run an actor which could be restarted by its supervisors
+    /// a restart keeps the same instance, `ActorRestart` resets it
+    pub async fn supervised_run<A: Actor + ActorRestart>(self, actor: A) -> Result<Addr<A>> {
+        self.supervised_start(actor, None).await
+    }
+
+    /// run an actor built by `factory` which could be restarted by its
+    /// supervisors
+    /// a restart drops the instance and builds a new one behind the same
+    /// `Addr`, the old one gets `on_stop` and the new one `on_start`
+    pub async fn supervised_run_with<A, F>(self, factory: F) -> Result<Addr<A>>
+    where
+        A: Actor + ActorRestart,
+        F: Fn() -> A + Send + Sync + 'static,
+    {
+        let actor = factory();
+        self.supervised_start(actor, Some(Box::new(factory))).await
+    }
+
+    async fn supervised_start<A: Actor + ActorRestart>(
+        self,
+        actor: A,
+        factory: Option<ActorFactory<A>>,
+    ) -> Result<Addr<A>> {
         let Self {
             ctx,
             mut rx,
             tx,
             tx_exit,
+            error_policy,
+            concurrency,
+            trap_exit,
+            restart_lifecycle,
+            ..
         } = self;
 
         let rx_exit = ctx.rx_exit.clone();
         let id = ctx.id;
-        ACTOR_ID_NAME.insert(id, None);
-        let actor = Arc::new(actor);
-        let addr = Addr { id, tx, rx_exit };
-        ctx.addr.set(addr.downgrade()).expect("addr is already set");
-        actor.on_start(&ctx).await?;
-        let weakaddr = addr.downgrade();
-        let handle = tokio::task::spawn(async move {
-            let mut exit_err = Ok(());
-            'supervising_loop: loop {
-                'event_loop: while let Some(event) = rx.next().await {
-                    match event {
-                        Event::Stop(err) => {
-                            exit_err = err;
-                            break 'event_loop;
+        let system = ctx.system.clone();
+        system.names().insert(id, None);
+        let mut actor = Arc::new(actor);
+        let addr = Addr::new(id, tx, rx_exit, system.clone());
+        ctx.addr
+            .set(addr.downgrade().erase())
+            .expect("addr is already set");
+        if let Err(err) = system.scope(actor.on_start(&ctx)).await {
+            // the actor never ran, forget the names it may have set
+            system.remove_name(id);
+            return Err(err);
+        }
+        system.add_actor(&ctx);
+        let weakaddr = addr.downgrade().erase();
+        let error_policy = error_policy.unwrap_or(ErrorPolicy::Restart);
+        let handle = tokio::task::spawn(system.scope(async move {
+            // false if `on_start` failed after a restart
+            let mut started = true;
+            let reason = loop {
+                let cause = match Self::event_loop(
+                    &mut actor,
+                    &ctx,
+                    &mut rx,
+                    error_policy,
+                    concurrency,
+                    trap_exit,
+                    true,
+                )
+                .await
+                {
+                    LoopExit::Restart => RestartCause::Requested,
+                    // supervice logic
+                    exit @ (LoopExit::Stop(Err(_))
+                    | LoopExit::Failed(_, ErrorPolicy::Restart)
+                    | LoopExit::Linked(..)) => {
+                        let reason = exit.into_reason();
+                        if ctx.supervisors.lock().await.is_empty() {
+                            break reason;
                         }
-                        Event::Exec(f) => match f(actor.clone(), &ctx).await {
-                            Ok(_) => {}
-                            Err(err) => {
-                                exit_err = Err(err);
-                                break 'event_loop;
-                            }
-                        },
-                        Event::Restart => {
-                            actor.on_restart(&weakaddr).await;
-                            continue 'supervising_loop;
+                        if let Err(err) = ctx.await_supervisor().await {
+                            break ExitReason::SupervisorGaveUp(Arc::new(err));
+                        }
+                        RestartCause::Failed(reason)
+                    }
+                    LoopExit::Failed(err, ErrorPolicy::Escalate) => {
+                        ctx.escalate(&err).await;
+                        break ExitReason::from_handler_error(err);
+                    }
+                    exit => break exit.into_reason(),
+                };
+                if let Err(err) = Self::restart(
+                    &mut actor,
+                    &ctx,
+                    &weakaddr,
+                    &cause,
+                    restart_lifecycle,
+                    factory.as_ref(),
+                )
+                .await
+                {
+                    started = false;
+                    break ExitReason::Stopped(Arc::new(err));
+                }
+            };
+            ctx.timers.cancel_all();
+            ctx.streams.cancel_all();
+            rx.close();
+            ctx.stop_children().await;
+            if started {
+                actor.on_stop(&ctx).await;
+            }
+            ctx.system.actor_exited(id);
+            tx_exit
+                .send(reason.clone())
+                .expect("tx_exit is already closed");
+            reason.into_result()
+        }));
+        system.set_handle(id, handle);
+        Ok(addr)
+    }
+
+    /// restart the actor, the supervisors already agreed
+    /// it first waits for the delay set by a supervisor, if any
+    /// with a factory the instance is replaced by a new one
+    async fn restart<A: Actor + ActorRestart>(
+        actor: &mut Arc<A>,
+        ctx: &Context,
+        weakaddr: &WeakAddr,
+        cause: &RestartCause,
+        lifecycle: bool,
+        factory: Option<&ActorFactory<A>>,
+    ) -> Result<()> {
+        let delay = ctx.system.take_restart_delay(ctx.id);
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+        ctx.timers.cancel_all();
+        actor.pre_restart(ctx, cause).await;
+        let lifecycle = lifecycle || factory.is_some();
+        if lifecycle {
+            actor.on_stop(ctx).await;
+        }
+        if let Some(factory) = factory {
+            *actor = Arc::new(factory());
+        }
+        actor.on_restart(weakaddr).await;
+        if lifecycle {
+            actor
+                .on_start(ctx)
+                .await
+                .map_err(|err| err.context("on_start failed after restart"))?;
+        }
+        ctx.streams.restart(ctx);
+        actor.post_restart(ctx).await;
+        Ok(())
+    }
+
+    /// handle events until the actor has to stop or restart
+    /// up to `concurrency` handlers are running at the same time, they are all
+    /// finished before leaving
+    /// a `MutHandler` waits for the running handlers and then runs alone, the
+    /// drain deadline still applies to them
+    async fn event_loop<A: Actor>(
+        actor: &mut Arc<A>,
+        ctx: &Context,
+        rx: &mut MailboxReceiver,
+        error_policy: ErrorPolicy,
+        concurrency: usize,
+        trap_exit: Option<ProxyExecFn<Exit>>,
+        supervised: bool,
+    ) -> LoopExit {
+        let mut in_flight = FuturesUnordered::new();
+        // the result to stop with once the mailbox is drained
+        let mut draining: Option<Result<()>> = None;
+        let mut deadline: Option<Instant> = None;
+        // system events received while a `MutHandler` runs
+        let mut deferred = VecDeque::new();
+        let mut exit = loop {
+            let event = match draining {
+                _ if !deferred.is_empty() => deferred.pop_front(),
+                Some(_) if in_flight.len() < concurrency => match rx.try_recv() {
+                    Some(event) => Some(event),
+                    None if in_flight.is_empty() => {
+                        break LoopExit::Stop(draining.take().expect("draining"));
+                    }
+                    None => None,
+                },
+                _ => None,
+            };
+            let room = draining.is_none() && in_flight.len() < concurrency;
+            let event = match event {
+                Some(event) => event,
+                None => tokio::select! {
+                    biased;
+                    _ = tokio::time::sleep_until(deadline.unwrap_or_else(Instant::now)),
+                        if deadline.is_some() =>
+                    {
+                        // the handlers in flight are dropped
+                        return Self::killed(actor, ctx);
+                    }
+                    Some(res) = in_flight.next(), if !in_flight.is_empty() => {
+                        if let Some(exit) = Self::handle_failure(actor, ctx, res, error_policy) {
+                            break exit;
                         }
-                        Event::AddSupervisor(proxy) => {
-                            ctx.supervisors.lock().await.push(proxy);
+                        continue;
+                    }
+                    // system events are received even without room for handlers
+                    event = rx.recv(room) => {
+                        match event {
+                            Some(event) => event,
+                            None => break LoopExit::Stop(Ok(())),
                         }
                     }
+                },
+            };
+            let event = match event {
+                Event::LinkExit(id, reason) => match trap_exit {
+                    Some(trap) => trap(Exit { id, reason }, None),
+                    None if reason.is_normal() => continue,
+                    None => break LoopExit::Linked(id, reason),
+                },
+                event => event,
+            };
+            match event {
+                Event::Stop(err, StopMode::Immediate) => break LoopExit::Stop(err),
+                Event::Stop(err, mode) => {
+                    if draining.is_none() {
+                        draining = Some(err);
+                    }
+                    if let StopMode::DrainWithDeadline(timeout) = mode {
+                        let at = Instant::now() + timeout;
+                        deadline = Some(deadline.map_or(at, |d| d.min(at)));
+                    }
                 }
-                // supervice logic
-                if exit_err.is_err() {
-                    exit_err = ctx.await_supervisor().await;
-                    if exit_err.is_err() {
-                        break 'supervising_loop;
-                    } else {
-                        actor.on_restart(&weakaddr).await;
-                        continue 'supervising_loop;
+                Event::Exec(f) => in_flight.push(f(actor.clone(), ctx)),
+                Event::ExecMut(f) => {
+                    let in_flight = async {
+                        let mut failed = None;
+                        while let Some(res) = in_flight.next().await {
+                            failed = failed
+                                .or_else(|| Self::handle_failure(actor, ctx, res, error_policy));
+                        }
+                        failed
+                    };
+                    match Self::until_deadline(in_flight, rx, &mut deadline, &mut deferred).await {
+                        None => return Self::killed(actor, ctx),
+                        Some(Some(exit)) => break exit,
+                        Some(None) => {}
                     }
-                } else {
-                    break 'supervising_loop;
+                    // the caller gets an error if an `ExecFn` still holds the actor
+                    let actor_mut =
+                        Arc::get_mut(actor).map(|actor| actor as &mut (dyn Any + Send + Sync));
+                    let handler = f(actor_mut, ctx);
+                    match Self::until_deadline(handler, rx, &mut deadline, &mut deferred).await {
+                        None => return Self::killed(actor, ctx),
+                        Some(res) => {
+                            if let Some(exit) = Self::handle_failure(actor, ctx, res, error_policy)
+                            {
+                                break exit;
+                            }
+                        }
+                    }
+                }
+                Event::Restart if supervised => break LoopExit::Restart,
+                Event::AddSupervisor(proxy) if supervised => {
+                    ctx.supervisors.lock().await.push(proxy);
                 }
+                _ => panic!("this event could only send by supervisor"),
             }
-            actor.on_stop(&ctx).await;
-            tx_exit.send(()).expect("tx_exit is already closed");
-            ACTOR_ID_HANDLE.remove(&id);
-            exit_err
-        });
-        ACTOR_ID_HANDLE.insert(id, handle);
-        Ok(addr)
+        };
+        while let Some(res) = in_flight.next().await {
+            if let Some(failed) = Self::handle_failure(actor, ctx, res, error_policy) {
+                if !matches!(exit, LoopExit::Failed(..)) {
+                    exit = failed;
+                }
+            }
+        }
+        exit
+    }
+
+    /// run `fut` until it is done, None if the drain deadline is reached first
+    /// the system events received meanwhile are kept in `deferred`, a new
+    /// deadline among them applies at once
+    async fn until_deadline<T>(
+        fut: impl Future<Output = T>,
+        rx: &mut MailboxReceiver,
+        deadline: &mut Option<Instant>,
+        deferred: &mut VecDeque<Event>,
+    ) -> Option<T> {
+        futures::pin_mut!(fut);
+        loop {
+            tokio::select! {
+                biased;
+                _ = tokio::time::sleep_until(deadline.unwrap_or_else(Instant::now)),
+                    if deadline.is_some() => return None,
+                res = &mut fut => return Some(res),
+                Some(event) = rx.recv(false) => {
+                    if let Event::Stop(_, StopMode::DrainWithDeadline(timeout)) = &event {
+                        let at = Instant::now() + *timeout;
+                        *deadline = Some(deadline.map_or(at, |d| d.min(at)));
+                    }
+                    deferred.push_back(event);
+                }
+            }
+        }
+    }
+
+    fn killed<A: Actor>(actor: &Arc<A>, ctx: &Context) -> LoopExit {
+        warn!(
+            "{} killed after the drain deadline",
+            actor.get_name_or_id_string(ctx)
+        );
+        LoopExit::Killed
+    }
+
+    fn handle_failure<A: Actor>(
+        actor: &Arc<A>,
+        ctx: &Context,
+        res: std::result::Result<(), ExecError>,
+        error_policy: ErrorPolicy,
+    ) -> Option<LoopExit> {
+        let (error, policy) = res.err()?.resolve(error_policy);
+        if policy == ErrorPolicy::ReplyOnly {
+            info!(
+                "{} handler failed: {:#}",
+                actor.get_name_or_id_string(ctx),
+                error
+            );
+            None
+        } else {
+            Some(LoopExit::Failed(error, policy))
+        }
+    }
+}
+
+/// why the event loop of an actor exits
+enum LoopExit {
+    /// stop event or all the addresses are dropped
+    Stop(Result<()>),
+    /// a handler failed with a policy other than `ReplyOnly`
+    Failed(anyhow::Error, ErrorPolicy),
+    /// restart event from a supervisor
+    Restart,
+    /// the drain deadline is reached
+    Killed,
+    /// a linked actor failed and the actor does not trap exits
+    Linked(ActorID, ExitReason),
+}
+
+impl LoopExit {
+    fn into_reason(self) -> ExitReason {
+        match self {
+            LoopExit::Stop(Ok(())) => ExitReason::Normal,
+            LoopExit::Stop(Err(err)) => ExitReason::Stopped(Arc::new(err)),
+            LoopExit::Failed(err, _) => ExitReason::from_handler_error(err),
+            LoopExit::Killed => ExitReason::Killed,
+            LoopExit::Linked(id, reason) => ExitReason::Linked(id, Box::new(reason)),
+            LoopExit::Restart => unreachable!("restart is not an exit"),
+        }
+    }
+}
+
+/// why an actor is restarted
+#[derive(Debug, Clone)]
+pub enum RestartCause {
+    /// the actor failed, with the reason it would have exited with
+    Failed(ExitReason),
+    /// a supervisor restarted it while it was running, e.g. `OneForAll`
+    Requested,
+}
+
+impl std::fmt::Display for RestartCause {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RestartCause::Failed(reason) => write!(f, "failed: {}", reason),
+            RestartCause::Requested => write!(f, "requested by a supervisor"),
+        }
     }
 }
 
 /// the default behavior of restarting an actor
+/// a restart calls `pre_restart`, `on_restart` then `post_restart`, see
+/// `ActorRunner::with_restart_lifecycle` to call `on_stop` and `on_start` too
 /// WARNING: default is do nothing
 #[async_trait::async_trait]
 pub trait ActorRestart {
+    /// called first, with what made the actor restart
+    async fn pre_restart(&self, _ctx: &Context, _cause: &RestartCause) {}
+
     #[tracing::instrument(
         skip(self),
         fields(addr = _addr.get_name_or_id_string().as_str())
@@ -226,4 +744,7 @@ pub trait ActorRestart {
     async fn on_restart(&self, _addr: &WeakAddr) {
         warn!("{} restarted", _addr.get_name_or_id_string().as_str());
     }
+
+    /// called last, the actor handles messages again after it
+    async fn post_restart(&self, _ctx: &Context) {}
 }
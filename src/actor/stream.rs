@@ -0,0 +1,311 @@
+use std::sync::{atomic::AtomicU64, Arc, Weak};
+
+use anyhow::Result;
+use dashmap::DashMap;
+use futures::{
+    channel::oneshot,
+    future::{AbortHandle, AbortRegistration, Abortable},
+    Stream, StreamExt,
+};
+
+use super::{
+    addr::{guard_panic, Event, ExecError, ExecFn},
+    context::Context,
+    mailbox::{MailboxSender, Priority},
+    runner::Actor,
+};
+
+/// handle the items of a stream attached by `Context::add_stream`
+/// the items are handled one by one, interleaved with the messages in the
+/// mailbox, the next item is pulled only after the previous one is handled
+/// an error of `handle` goes through the error policy of the actor
+#[async_trait::async_trait]
+pub trait StreamHandler<I: Send + 'static>: Actor {
+    /// called before the first item of the stream
+    async fn started(&self, _ctx: &Context) {}
+
+    async fn handle(&self, ctx: &Context, item: I) -> Result<()>;
+
+    /// called when the stream ends, not when it is cancelled
+    async fn finished(&self, _ctx: &Context) {}
+}
+
+/// what happens to an attached stream when the actor restarts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamRestart {
+    /// keep pulling the same stream after the restart
+    Resume,
+    /// drop the stream and attach a new one from the factory
+    Reattach,
+    /// drop the stream
+    Cancel,
+}
+
+/// handle of a stream attached to an actor
+/// dropping it does not cancel the stream
+#[derive(Clone)]
+pub struct StreamHandle {
+    id: u64,
+    streams: Weak<Streams>,
+}
+
+impl StreamHandle {
+    /// detach the stream, an item already in the mailbox is still handled
+    pub fn cancel(&self) {
+        if let Some(streams) = self.streams.upgrade() {
+            if let Some((_, stream)) = streams.attached.remove(&self.id) {
+                stream.abort.abort();
+            }
+        }
+    }
+
+    /// check if the stream is still attached, false once it is finished or
+    /// cancelled
+    pub fn is_attached(&self) -> bool {
+        self.streams
+            .upgrade()
+            .map_or(false, |streams| streams.attached.contains_key(&self.id))
+    }
+}
+
+impl std::fmt::Debug for StreamHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<StreamHandle: {}>", self.id)
+    }
+}
+
+type AttachFn = Arc<dyn Fn(&Context, Pump, AbortRegistration) + Send + Sync>;
+
+/// the streams attached to an actor
+#[derive(Default)]
+pub(crate) struct Streams {
+    next_id: AtomicU64,
+    attached: DashMap<u64, AttachedStream>,
+}
+
+struct AttachedStream {
+    /// the pump currently pulling the stream
+    pump: u64,
+    abort: AbortHandle,
+    policy: StreamRestart,
+    attach: Option<AttachFn>,
+}
+
+impl Streams {
+    fn next_id(&self) -> u64 {
+        self.next_id
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// cancel all the attached streams
+    pub(crate) fn cancel_all(&self) {
+        self.attached.retain(|_, stream| {
+            stream.abort.abort();
+            false
+        });
+    }
+
+    /// apply the restart policy of each attached stream
+    pub(crate) fn restart(self: &Arc<Self>, ctx: &Context) {
+        let mut reattach = vec![];
+        self.attached.retain(|id, stream| match stream.policy {
+            StreamRestart::Resume => true,
+            StreamRestart::Cancel => {
+                stream.abort.abort();
+                false
+            }
+            StreamRestart::Reattach => {
+                stream.abort.abort();
+                if let Some(attach) = stream.attach.clone() {
+                    reattach.push((*id, attach));
+                }
+                false
+            }
+        });
+        for (id, attach) in reattach {
+            self.attach(ctx, id, StreamRestart::Reattach, attach);
+        }
+    }
+
+    /// start pumping a new stream built by `attach`
+    fn attach(
+        self: &Arc<Self>,
+        ctx: &Context,
+        id: u64,
+        policy: StreamRestart,
+        attach: AttachFn,
+    ) -> StreamHandle {
+        let spawn = attach.clone();
+        self.spawn(ctx, id, policy, Some(attach), |ctx, pump, abort| {
+            spawn(ctx, pump, abort)
+        })
+    }
+
+    /// start pumping the stream with `id`, replaces the previous pump
+    fn spawn(
+        self: &Arc<Self>,
+        ctx: &Context,
+        id: u64,
+        policy: StreamRestart,
+        attach: Option<AttachFn>,
+        spawn: impl FnOnce(&Context, Pump, AbortRegistration),
+    ) -> StreamHandle {
+        let pump = Pump {
+            stream: id,
+            id: self.next_id(),
+            streams: Arc::downgrade(self),
+        };
+        let (abort, registration) = AbortHandle::new_pair();
+        // inserted first, a pump which ends at once removes it when dropped
+        self.attached.insert(
+            id,
+            AttachedStream {
+                pump: pump.id,
+                abort,
+                policy,
+                attach,
+            },
+        );
+        spawn(ctx, pump, registration);
+        StreamHandle {
+            id,
+            streams: Arc::downgrade(self),
+        }
+    }
+}
+
+/// the task pulling a stream, detaches the stream when it is finished
+pub(crate) struct Pump {
+    stream: u64,
+    id: u64,
+    streams: Weak<Streams>,
+}
+
+impl Drop for Pump {
+    fn drop(&mut self) {
+        if let Some(streams) = self.streams.upgrade() {
+            streams
+                .attached
+                .remove_if(&self.stream, |_, stream| stream.pump == self.id);
+        }
+    }
+}
+
+enum StreamEvent<I> {
+    Started,
+    Item(I),
+    Finished,
+}
+
+/// build the exec function which runs the hook of `A` for `event`
+/// `tx` is notified once it is handled
+fn stream_exec<A: StreamHandler<I>, I: Send + 'static>(
+    event: StreamEvent<I>,
+    tx: oneshot::Sender<()>,
+) -> ExecFn {
+    Box::new(move |actor, ctx| {
+        Box::pin(async move {
+            let handler = actor.as_ref().downcast_ref::<A>().ok_or_else(|| {
+                anyhow::anyhow!(
+                    "error: {} trying to handle a stream in actor which is not a {}",
+                    std::any::type_name_of_val(&actor),
+                    std::any::type_name::<A>()
+                )
+            })?;
+            let res = match event {
+                StreamEvent::Started => {
+                    handler.started(ctx).await;
+                    Ok(())
+                }
+                StreamEvent::Item(item) => guard_panic(handler.handle(ctx, item)).await,
+                StreamEvent::Finished => {
+                    handler.finished(ctx).await;
+                    Ok(())
+                }
+            };
+            let _ = tx.send(());
+            res.map_err(ExecError::from)
+        })
+    })
+}
+
+/// put the event into the mailbox and wait until it is handled
+/// fails if the actor is stopped
+async fn deliver<A: StreamHandler<I>, I: Send + 'static>(
+    tx: &Weak<MailboxSender>,
+    event: StreamEvent<I>,
+) -> std::result::Result<(), ()> {
+    let (done_tx, done_rx) = oneshot::channel();
+    tx.upgrade()
+        .ok_or(())?
+        .send(
+            Event::Exec(stream_exec::<A, I>(event, done_tx)),
+            Priority::Normal,
+        )
+        .await
+        .map_err(|_| ())?;
+    done_rx.await.map_err(|_| ())
+}
+
+fn spawn_pump<A, I, S>(ctx: &Context, stream: S, pump: Pump, abort: AbortRegistration)
+where
+    A: StreamHandler<I>,
+    I: Send + 'static,
+    S: Stream<Item = I> + Send + 'static,
+{
+    let tx = ctx.tx.clone();
+    let task = Abortable::new(
+        async move {
+            let _pump = pump;
+            futures::pin_mut!(stream);
+            if deliver::<A, I>(&tx, StreamEvent::Started).await.is_err() {
+                return;
+            }
+            while let Some(item) = stream.next().await {
+                if deliver::<A, I>(&tx, StreamEvent::Item(item)).await.is_err() {
+                    return;
+                }
+            }
+            let _ = deliver::<A, I>(&tx, StreamEvent::Finished).await;
+        },
+        abort,
+    );
+    tokio::task::spawn(task);
+}
+
+impl Context {
+    /// attach a stream to this actor, its items are handled by
+    /// `StreamHandler::handle`
+    /// `A` is the type of this actor
+    /// the stream is cancelled when the actor stops and resumed when it
+    /// restarts
+    pub fn add_stream<A, I, S>(&self, stream: S) -> StreamHandle
+    where
+        A: StreamHandler<I>,
+        I: Send + 'static,
+        S: Stream<Item = I> + Send + 'static,
+    {
+        let id = self.streams.next_id();
+        self.streams
+            .spawn(self, id, StreamRestart::Resume, None, |ctx, pump, abort| {
+                spawn_pump::<A, I, S>(ctx, stream, pump, abort)
+            })
+    }
+
+    /// attach the stream built by `factory` to this actor
+    /// `A` is the type of this actor
+    /// the stream is cancelled when the actor stops, `policy` decides what
+    /// happens to it when the actor restarts
+    pub fn add_stream_with<A, I, S, F>(&self, factory: F, policy: StreamRestart) -> StreamHandle
+    where
+        A: StreamHandler<I>,
+        I: Send + 'static,
+        S: Stream<Item = I> + Send + 'static,
+        F: Fn() -> S + Send + Sync + 'static,
+    {
+        let id = self.streams.next_id();
+        let attach: AttachFn =
+            Arc::new(move |ctx, pump, abort| spawn_pump::<A, I, S>(ctx, factory(), pump, abort));
+        self.streams.attach(self, id, policy, attach)
+    }
+}
@@ -1,12 +1,21 @@
-use crate::actor::{
-    addr::Addr,
-    message::{Handler, Message},
-    runner::{Actor, ActorID},
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Result;
+use futures::future::BoxFuture;
+
+use crate::{
+    actor::{
+        addr::Addr,
+        message::{Handler, Message},
+        runner::{Actor, ActorID, ActorRestart, ActorRunner, ErrorPolicy},
+    },
+    utils::backoff::Backoff,
 };
 
 /// The supervisor is responsible for restarting actors.
 /// enable feature "supervisor_catch_unwind" for catch normal panics
 /// it will slow down the program but it is more safe
+/// a caught panic is a `HandlerPanic` failure, which is never `ReplyOnly`
 /// **supervisor must hold the address of the supervised actor!!**
 #[async_trait::async_trait]
 pub trait Supervisor: Actor + Handler<Restart> + Handler<Supervise> {}
@@ -25,3 +34,195 @@ pub struct Unsupervise(pub Addr);
 impl Message for Unsupervise {
     type Result = ();
 }
+
+/// when a supervisor restarts a child started from a `ChildSpec`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChildRestart {
+    /// always, a child which stops normally is started again
+    Permanent,
+    /// only after an abnormal exit
+    Transient,
+    /// never
+    Temporary,
+}
+
+type StartFn = Arc<dyn Fn(ActorRunner) -> BoxFuture<'static, Result<Addr>> + Send + Sync>;
+
+/// how a supervisor starts and stops one of its children
+#[derive(Clone)]
+pub struct ChildSpec {
+    start: StartFn,
+    pub restart: ChildRestart,
+    /// how long the child has to stop when the supervisor stops, then it is
+    /// killed
+    pub shutdown: Duration,
+    /// the backoff of the child, the one of the supervisor if None
+    pub backoff: Option<Backoff>,
+}
+
+impl ChildSpec {
+    /// a permanent child built by `factory`, with a 5s shutdown timeout
+    /// a restart builds a new instance, see `ActorRunner::supervised_run_with`
+    pub fn new<A, F>(factory: F) -> Self
+    where
+        A: Actor + ActorRestart,
+        F: Fn() -> A + Send + Sync + 'static,
+    {
+        let factory = Arc::new(factory);
+        Self {
+            start: Arc::new(move |runner| {
+                let factory = factory.clone();
+                Box::pin(async move {
+                    let addr = runner.supervised_run_with(move || factory()).await?;
+                    Ok(addr.erase())
+                })
+            }),
+            restart: ChildRestart::Permanent,
+            shutdown: Duration::from_secs(5),
+            backoff: None,
+        }
+    }
+
+    pub fn with_restart(mut self, restart: ChildRestart) -> Self {
+        self.restart = restart;
+        self
+    }
+
+    pub fn with_shutdown(mut self, shutdown: Duration) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    /// the backoff of the child, set each time it is started like `SetBackoff`
+    pub fn with_backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = Some(backoff);
+        self
+    }
+
+    /// run the child with `runner`
+    pub async fn start(&self, runner: ActorRunner) -> Result<Addr> {
+        (self.start)(runner).await
+    }
+}
+
+impl std::fmt::Debug for ChildSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChildSpec")
+            .field("restart", &self.restart)
+            .field("shutdown", &self.shutdown)
+            .field("backoff", &self.backoff)
+            .finish()
+    }
+}
+
+/// ask a supervisor to start and supervise a child
+/// the supervisor owns it and stops it when the supervisor stops
+pub struct StartChild(pub ChildSpec);
+impl Message for StartChild {
+    type Result = Addr;
+
+    const ERROR_POLICY: Option<ErrorPolicy> = Some(ErrorPolicy::ReplyOnly);
+}
+
+/// status of a supervised actor, see `WhichChildren`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChildStatus {
+    Running,
+    /// stopped by `TerminateChild`, `RestartChild` starts it again
+    Terminated,
+}
+
+/// a supervised actor, see `WhichChildren`
+#[derive(Debug, Clone)]
+pub struct ChildInfo {
+    /// the id of the last instance, a child started again has a new id
+    pub id: ActorID,
+    pub name: Option<String>,
+    pub status: ChildStatus,
+    /// how many times it is restarted or started again
+    pub restarts: usize,
+}
+
+/// the supervised actors in supervision order
+/// an actor supervised by `Supervise` is forgotten once it exits
+pub struct WhichChildren;
+impl Message for WhichChildren {
+    type Result = Vec<ChildInfo>;
+}
+
+/// the number of supervised actors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ChildCount {
+    /// including the terminated ones
+    pub total: usize,
+    pub running: usize,
+}
+
+pub struct CountChildren;
+impl Message for CountChildren {
+    type Result = ChildCount;
+}
+
+/// stop a supervised actor without restarting it
+/// a child started from a `ChildSpec` is kept as `ChildStatus::Terminated`,
+/// the other actors are no longer supervised
+pub struct TerminateChild(pub ActorID);
+impl Message for TerminateChild {
+    type Result = ();
+
+    const ERROR_POLICY: Option<ErrorPolicy> = Some(ErrorPolicy::ReplyOnly);
+}
+
+/// start a terminated child again, or restart a running actor in place
+/// replies the address of the child
+pub struct RestartChild(pub ActorID);
+impl Message for RestartChild {
+    type Result = Addr;
+
+    const ERROR_POLICY: Option<ErrorPolicy> = Some(ErrorPolicy::ReplyOnly);
+}
+
+/// forget a terminated child
+/// fails if the child is running
+pub struct DeleteChild(pub ActorID);
+impl Message for DeleteChild {
+    type Result = ();
+
+    const ERROR_POLICY: Option<ErrorPolicy> = Some(ErrorPolicy::ReplyOnly);
+}
+
+/// set the backoff of a supervised actor, overriding the one of the
+/// supervisor
+/// fails if the actor is not supervised
+pub struct SetBackoff(pub ActorID, pub Backoff);
+impl Message for SetBackoff {
+    type Result = ();
+
+    const ERROR_POLICY: Option<ErrorPolicy> = Some(ErrorPolicy::ReplyOnly);
+}
+
+/// failure of a handler which panicked
+/// only caught with feature "supervisor_catch_unwind"
+#[derive(Debug, Clone)]
+pub struct HandlerPanic(pub String);
+
+impl HandlerPanic {
+    #[cfg(feature = "supervisor_catch_unwind")]
+    pub(crate) fn from_payload(payload: Box<dyn std::any::Any + Send>) -> Self {
+        if let Some(msg) = payload.downcast_ref::<&str>() {
+            Self(msg.to_string())
+        } else if let Some(msg) = payload.downcast_ref::<String>() {
+            Self(msg.clone())
+        } else {
+            Self("unknown panic".to_string())
+        }
+    }
+}
+
+impl std::fmt::Display for HandlerPanic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "error: handler panicked: {}", self.0)
+    }
+}
+
+impl std::error::Error for HandlerPanic {}
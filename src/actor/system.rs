@@ -0,0 +1,409 @@
+use std::{
+    collections::HashSet,
+    lazy::SyncLazy,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use anyhow::Result;
+use dashmap::DashMap;
+use futures::{future::join_all, Future};
+use tokio::{sync::watch, task::JoinHandle, time::Instant};
+use tracing::warn;
+
+use super::{
+    addr::{Addr, WeakAddr},
+    context::Context,
+    mailbox::MailboxCapacity,
+    registry::Registered,
+    runner::{Actor, ActorID, ActorRestart, ActorRunner, StopMode},
+};
+use crate::utils::service::Registry;
+
+/// the system used by `Actor::spawn`, `ActorRunner::new` and the free
+/// functions such as `whereis` and `await_exit`
+static GLOBAL_SYSTEM: SyncLazy<ActorSystem> = SyncLazy::new(ActorSystem::new);
+
+tokio::task_local! {
+    /// the system of `ActorSystem::scope`, it replaces `GLOBAL_SYSTEM`
+    static CURRENT_SYSTEM: ActorSystem;
+}
+
+/// a deprecated static which derefs to a part of the default system
+#[doc(hidden)]
+pub struct GlobalShim<T: 'static>(pub(crate) fn(&'static ActorSystem) -> &'static T);
+
+impl<T> std::ops::Deref for GlobalShim<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        (self.0)(&GLOBAL_SYSTEM)
+    }
+}
+
+/// the running actors of the default system, see `ACTOR_ID_HANDLE`
+#[doc(hidden)]
+pub struct GlobalHandles(pub(crate) ());
+
+impl GlobalHandles {
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn len(&self) -> usize {
+        GLOBAL_SYSTEM.actor_count()
+    }
+
+    pub fn contains_key(&self, id: &ActorID) -> bool {
+        GLOBAL_SYSTEM.is_running(*id)
+    }
+}
+
+/// configuration of an `ActorSystem`
+#[derive(Debug, Clone)]
+pub struct ActorSystemConfig {
+    /// how long `Addr::is_stopped` waits, default to 10ms (or
+    /// `ACTOR_STOP_WAIT_INTERVAL`)
+    pub stop_wait_interval: Duration,
+}
+
+impl Default for ActorSystemConfig {
+    #[allow(deprecated)]
+    fn default() -> Self {
+        Self {
+            // SAFETY: only written by users of the deprecated static
+            stop_wait_interval: unsafe { super::addr::ACTOR_STOP_WAIT_INTERVAL },
+        }
+    }
+}
+
+/// a set of actors with their own ids, names and services
+/// actors of different systems never see each other by id or name
+/// clone as you want, the clones share the same system
+#[derive(Clone)]
+pub struct ActorSystem(Arc<SystemState>);
+
+struct SystemState {
+    config: ActorSystemConfig,
+    next_id: AtomicU64,
+    names: DashMap<ActorID, Option<String>>,
+    actors: DashMap<ActorID, RunningActor>,
+    registry: DashMap<String, Registered>,
+    /// the name each actor is registered with, the display name in `names`
+    /// may be changed by `set_name` without touching the registry
+    registered: DashMap<ActorID, String>,
+    services: Registry,
+    /// changed every time an actor exits
+    exited: (watch::Sender<()>, watch::Receiver<()>),
+    /// true while `shutdown` is running
+    shutting_down: AtomicBool,
+}
+
+/// a running actor of the system
+struct RunningActor {
+    addr: WeakAddr,
+    handle: Option<JoinHandle<Result<()>>>,
+    parent: Option<ActorID>,
+    supervisors: Vec<ActorID>,
+    /// how long the next restart waits, set by a supervisor
+    restart_delay: Duration,
+}
+
+/// result of `ActorSystem::shutdown`
+#[derive(Debug, Clone, Default)]
+pub struct ShutdownReport {
+    /// the number of actors stopped before the timeout
+    pub stopped: usize,
+    /// the actors aborted when the timeout expired, with their names
+    pub killed: Vec<(ActorID, Option<String>)>,
+}
+
+impl ShutdownReport {
+    /// all the actors stopped before the timeout
+    pub fn is_clean(&self) -> bool {
+        self.killed.is_empty()
+    }
+}
+
+impl Default for ActorSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ActorSystem {
+    /// a new system with the default configuration
+    pub fn new() -> Self {
+        Self::with_config(ActorSystemConfig::default())
+    }
+
+    pub fn with_config(config: ActorSystemConfig) -> Self {
+        Self(Arc::new(SystemState {
+            config,
+            next_id: AtomicU64::new(0),
+            names: DashMap::new(),
+            actors: DashMap::new(),
+            registry: DashMap::new(),
+            registered: DashMap::new(),
+            services: DashMap::new(),
+            exited: watch::channel(()),
+            shutting_down: AtomicBool::new(false),
+        }))
+    }
+
+    /// the default system
+    /// it is the system of the running actor in its handlers, the one of the
+    /// enclosing `scope`, or else the global one
+    pub fn global() -> Self {
+        CURRENT_SYSTEM
+            .try_with(Clone::clone)
+            .unwrap_or_else(|_| GLOBAL_SYSTEM.clone())
+    }
+
+    /// run `fut` with this system as the default one, so `Actor::spawn`,
+    /// `ActorRunner::new` and the free functions such as `whereis` and
+    /// `await_exit` use it
+    pub fn scope<F: Future>(&self, fut: F) -> impl Future<Output = F::Output> {
+        CURRENT_SYSTEM.scope(self.clone(), fut)
+    }
+
+    pub fn config(&self) -> &ActorSystemConfig {
+        &self.0.config
+    }
+
+    /// runner with an unbounded mailbox in this system
+    pub fn runner(&self) -> ActorRunner {
+        self.runner_with_capacity(MailboxCapacity::Unbounded)
+    }
+
+    /// runner with the given mailbox capacity in this system
+    ///
+    /// # Panics
+    ///
+    /// panics if the capacity is `MailboxCapacity::Bounded(0)`
+    pub fn runner_with_capacity(&self, capacity: MailboxCapacity) -> ActorRunner {
+        ActorRunner::in_system(self.clone(), capacity)
+    }
+
+    /// like `Actor::spawn` but in this system
+    pub async fn spawn<A: Actor>(&self, actor: A) -> Result<Addr<A>> {
+        self.runner().run(actor).await
+    }
+
+    /// like `Actor::spawn_supervisable` but in this system
+    pub async fn spawn_supervisable<A: Actor + ActorRestart>(&self, actor: A) -> Result<Addr<A>> {
+        self.runner().supervised_run(actor).await
+    }
+
+    /// like `Actor::spawn_supervised_with` but in this system
+    pub async fn spawn_supervised_with<A, F>(&self, factory: F) -> Result<Addr<A>>
+    where
+        A: Actor + ActorRestart,
+        F: Fn() -> A + Send + Sync + 'static,
+    {
+        self.runner().supervised_run_with(factory).await
+    }
+
+    /// the number of running actors
+    pub fn actor_count(&self) -> usize {
+        self.0.actors.len()
+    }
+
+    /// check if the actor with `id` is running
+    pub fn is_running(&self, id: ActorID) -> bool {
+        self.0.actors.contains_key(&id)
+    }
+
+    /// the service registry of this system
+    pub fn service_registry(&self) -> &Registry {
+        &self.0.services
+    }
+
+    /// wait until all the actors of this system are stopped
+    /// it never returns while a long-lived actor is running, see `shutdown`
+    pub async fn await_exit(&self) {
+        let mut exited = self.0.exited.1.clone();
+        while !self.0.actors.is_empty() {
+            if exited.changed().await.is_err() {
+                break;
+            }
+        }
+    }
+
+    /// stop all the actors and wait for them
+    /// the actors are stopped in waves: a supervisor waits for the actors it
+    /// supervises, a parent stops its own children first
+    /// the actors still running when `timeout` expires are aborted without
+    /// `on_stop` and listed in the report
+    pub async fn shutdown(&self, timeout: Duration) -> ShutdownReport {
+        self.0.shutting_down.store(true, Ordering::SeqCst);
+        let deadline = Instant::now() + timeout;
+        let mut report = ShutdownReport::default();
+        loop {
+            let wave = self.next_wave();
+            if wave.is_empty() {
+                break;
+            }
+            let exits = wave.iter().map(|addr| addr.await_stop());
+            for addr in &wave {
+                if let Some(addr) = addr.upgrade() {
+                    addr.stop_with_mode(Ok(()), StopMode::Immediate);
+                }
+            }
+            if tokio::time::timeout_at(deadline, join_all(exits))
+                .await
+                .is_err()
+            {
+                break;
+            }
+            report.stopped += wave.len();
+        }
+        for id in self.running_ids() {
+            let name = self.name(id);
+            if self.abort_actor(id).is_some() {
+                warn!("actor {} is killed by shutdown", id);
+                report.killed.push((id, name));
+            }
+        }
+        self.0.shutting_down.store(false, Ordering::SeqCst);
+        report
+    }
+
+    /// check if `shutdown` is running
+    /// a supervisor does not start its stopped children again meanwhile
+    pub fn is_shutting_down(&self) -> bool {
+        self.0.shutting_down.load(Ordering::SeqCst)
+    }
+
+    /// the actors which could be stopped now: not stopped by a parent and
+    /// not supervising or owning a running actor
+    fn next_wave(&self) -> Vec<WeakAddr> {
+        let running: HashSet<ActorID> = self.running_ids().into_iter().collect();
+        let mut blocked = HashSet::new();
+        for actor in self.0.actors.iter() {
+            blocked.extend(actor.supervisors.iter().copied());
+        }
+        // an ancestor of a blocked actor would stop it too early
+        for id in blocked.clone() {
+            let mut parent = self.0.actors.get(&id).and_then(|actor| actor.parent);
+            while let Some(id) = parent {
+                if !blocked.insert(id) {
+                    break;
+                }
+                parent = self.0.actors.get(&id).and_then(|actor| actor.parent);
+            }
+        }
+        let roots = self
+            .0
+            .actors
+            .iter()
+            .filter(|actor| !actor.parent.map_or(false, |id| running.contains(&id)))
+            .map(|actor| (blocked.contains(actor.key()), actor.addr.clone()))
+            .collect::<Vec<_>>();
+        let wave = roots
+            .iter()
+            .filter(|(blocked, _)| !blocked)
+            .map(|(_, addr)| addr.clone())
+            .collect::<Vec<_>>();
+        match wave.is_empty() {
+            // supervisors of each other
+            true => roots.into_iter().map(|(_, addr)| addr).collect(),
+            false => wave,
+        }
+    }
+
+    fn running_ids(&self) -> Vec<ActorID> {
+        self.0.actors.iter().map(|actor| *actor.key()).collect()
+    }
+
+    pub(crate) fn next_id(&self) -> ActorID {
+        self.0.next_id.fetch_add(1, Ordering::Relaxed)
+    }
+
+    pub(crate) fn names(&self) -> &DashMap<ActorID, Option<String>> {
+        &self.0.names
+    }
+
+    pub(crate) fn name(&self, id: ActorID) -> Option<String> {
+        self.0.names.get(&id)?.clone()
+    }
+
+    /// track an actor which is about to run its event loop
+    pub(crate) fn add_actor(&self, ctx: &Context) {
+        self.0.actors.insert(
+            ctx.id,
+            RunningActor {
+                addr: ctx.weak_address(),
+                handle: None,
+                parent: ctx.parent.get().map(|parent| parent.id),
+                supervisors: vec![],
+                restart_delay: Duration::ZERO,
+            },
+        );
+    }
+
+    /// the handle is dropped if the actor already exited
+    pub(crate) fn set_handle(&self, id: ActorID, handle: JoinHandle<Result<()>>) {
+        if let Some(mut actor) = self.0.actors.get_mut(&id) {
+            actor.handle = Some(handle);
+        }
+    }
+
+    pub(crate) fn add_supervisor(&self, id: ActorID, supervisor: ActorID) {
+        if let Some(mut actor) = self.0.actors.get_mut(&id) {
+            actor.supervisors.push(supervisor);
+        }
+    }
+
+    /// make the next restart of the actor wait for `delay`
+    /// the actor waits on its own, the supervisor keeps handling messages
+    pub(crate) fn delay_restart(&self, id: ActorID, delay: Duration) {
+        if let Some(mut actor) = self.0.actors.get_mut(&id) {
+            actor.restart_delay = delay;
+        }
+    }
+
+    /// the delay of the next restart of the actor, reset to zero
+    pub(crate) fn take_restart_delay(&self, id: ActorID) -> Duration {
+        self.0
+            .actors
+            .get_mut(&id)
+            .map_or(Duration::ZERO, |mut actor| {
+                std::mem::take(&mut actor.restart_delay)
+            })
+    }
+
+    /// forget an exited actor and wake up `await_exit`
+    pub(crate) fn actor_exited(&self, id: ActorID) {
+        self.0.actors.remove(&id);
+        self.remove_name(id);
+        let _ = self.0.exited.0.send(());
+    }
+
+    /// abort the task of the actor, None if it is not running
+    pub(crate) fn abort_actor(&self, id: ActorID) -> Option<()> {
+        let (_, actor) = self.0.actors.remove(&id)?;
+        if let Some(handle) = actor.handle {
+            handle.abort();
+        }
+        self.actor_exited(id);
+        Some(())
+    }
+
+    pub(crate) fn registry(&self) -> &DashMap<String, Registered> {
+        &self.0.registry
+    }
+
+    pub(crate) fn registered(&self) -> &DashMap<ActorID, String> {
+        &self.0.registered
+    }
+}
+
+impl std::fmt::Debug for ActorSystem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<ActorSystem: {} actors>", self.actor_count())
+    }
+}
@@ -0,0 +1,151 @@
+use std::{
+    sync::{atomic::AtomicU64, Arc, Weak},
+    time::Duration,
+};
+
+use dashmap::DashMap;
+use futures::{
+    future::{abortable, AbortHandle},
+    Future,
+};
+
+use super::{
+    context::Context,
+    message::{Dispatch, Message},
+};
+
+/// handle of a timer started on the `Context`
+/// dropping it does not cancel the timer
+#[derive(Clone)]
+pub struct TimerHandle(AbortHandle);
+
+impl TimerHandle {
+    /// cancel the timer, a message already in the mailbox is still handled
+    pub fn cancel(&self) {
+        self.0.abort();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.is_aborted()
+    }
+}
+
+impl std::fmt::Debug for TimerHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<TimerHandle: cancelled={}>", self.is_cancelled())
+    }
+}
+
+/// the running timers of an actor
+#[derive(Default)]
+pub(crate) struct Timers {
+    next_id: AtomicU64,
+    running: DashMap<u64, AbortHandle>,
+}
+
+impl Timers {
+    /// cancel all the running timers
+    pub(crate) fn cancel_all(&self) {
+        self.running.retain(|_, handle| {
+            handle.abort();
+            false
+        });
+    }
+}
+
+/// removes the timer from `Timers` when it is finished or cancelled
+struct TimerGuard {
+    id: u64,
+    timers: Weak<Timers>,
+}
+
+impl Drop for TimerGuard {
+    fn drop(&mut self) {
+        if let Some(timers) = self.timers.upgrade() {
+            timers.running.remove(&self.id);
+        }
+    }
+}
+
+impl Context {
+    /// send `msg` to this actor after `delay`
+    /// `A` is the type of this actor
+    /// cancelled when the actor stops or restarts
+    pub fn notify_later<A: Dispatch<T, K>, T: Message, K>(
+        &self,
+        msg: T,
+        delay: Duration,
+    ) -> TimerHandle {
+        self.run_later::<A, T, _, K>(delay, move || msg)
+    }
+
+    /// send the message built by `f` to this actor after `delay`
+    /// `A` is the type of this actor
+    /// cancelled when the actor stops or restarts
+    pub fn run_later<A, T, F, K>(&self, delay: Duration, f: F) -> TimerHandle
+    where
+        A: Dispatch<T, K>,
+        T: Message,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let tx = self.tx.clone();
+        self.spawn_timer(async move {
+            tokio::time::sleep(delay).await;
+            if let Some(tx) = tx.upgrade() {
+                let _ = tx.send(A::exec_event(f(), None), T::PRIORITY).await;
+            }
+        })
+    }
+
+    /// send the message built by `f` to this actor every `interval`, the
+    /// first one after `interval`
+    /// `A` is the type of this actor
+    /// cancelled when the actor stops or restarts
+    ///
+    /// # Panics
+    ///
+    /// panics if `interval` is zero
+    pub fn run_interval<A, T, F, K>(&self, interval: Duration, mut f: F) -> TimerHandle
+    where
+        A: Dispatch<T, K>,
+        T: Message,
+        F: FnMut() -> T + Send + 'static,
+    {
+        let tx = self.tx.clone();
+        let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+        self.spawn_timer(async move {
+            loop {
+                ticker.tick().await;
+                let tx = match tx.upgrade() {
+                    Some(tx) => tx,
+                    None => break,
+                };
+                if tx
+                    .send(A::exec_event(f(), None), T::PRIORITY)
+                    .await
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        })
+    }
+
+    fn spawn_timer(&self, timer: impl Future<Output = ()> + Send + 'static) -> TimerHandle {
+        let id = self
+            .timers
+            .next_id
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let guard = TimerGuard {
+            id,
+            timers: Arc::downgrade(&self.timers),
+        };
+        let (timer, handle) = abortable(async move {
+            let _guard = guard;
+            timer.await
+        });
+        self.timers.running.insert(id, handle.clone());
+        tokio::task::spawn(timer);
+        TimerHandle(handle)
+    }
+}
@@ -44,7 +44,7 @@
 //! async fn main() -> Result<()> {
 //!     let hello_actor = HelloActor;
 //!     let hello_actor_address = hello_actor.spawn().await?;
-//!     hello_actor_address.call::<HelloActor, Hello>(Hello).await
+//!     hello_actor_address.call(Hello).await
 //! }
 //! ```
 //!
@@ -54,7 +54,8 @@
 #![feature(type_name_of_val)]
 #![feature(once_cell)]
 
-use actor::runner::ACTOR_ID_HANDLE;
+use std::time::Duration;
+
 use futures::Future;
 
 #[cfg(test)]
@@ -72,13 +73,22 @@ pub use actor::*;
 /// exports to the derive macro
 #[inline(always)]
 pub async fn await_exit() {
-    loop {
-        if ACTOR_ID_HANDLE.is_empty() {
-            break;
-        } else {
-            tokio::time::sleep(std::time::Duration::from_millis(1)).await;
-        }
-    }
+    actor::system::ActorSystem::global().await_exit().await
+}
+
+/// exports to the derive macro
+/// run `future` in a new system and wait for the actors of that system only
+pub async fn run_in_new_system<F: Future>(future: F) -> F::Output {
+    let system = actor::system::ActorSystem::new();
+    let res = system.scope(future).await;
+    system.await_exit().await;
+    res
+}
+
+/// stop all the actors of the default system
+/// see `ActorSystem::shutdown`
+pub async fn shutdown(timeout: Duration) -> actor::system::ShutdownReport {
+    actor::system::ActorSystem::global().shutdown(timeout).await
 }
 
 /// exports to the derive macro
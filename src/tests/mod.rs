@@ -1,8 +1,28 @@
 mod test_actor;
+mod test_backoff;
 mod test_broker;
+#[cfg(feature = "supervisor_catch_unwind")]
+mod test_catch_unwind;
+mod test_child_spec;
+mod test_concurrency;
+mod test_context;
+mod test_error_policy;
+mod test_exit_reason;
+mod test_hierarchy;
+mod test_link;
+mod test_mailbox;
 mod test_message;
+mod test_mut_handler;
+mod test_priority;
 mod test_proxy;
+mod test_registry;
+mod test_restart;
+mod test_shutdown;
+mod test_stop;
+mod test_stream;
 mod test_supervisor;
+mod test_system;
+mod test_timer;
 
 use crate::{self as xtor, Actor, Context, Handler, Message};
 
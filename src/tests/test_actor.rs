@@ -5,8 +5,16 @@ async fn test_strong_to_weak_to_strong() {
     let actor = TestActor.spawn().await.unwrap();
     let weak = actor.downgrade();
     let strong = weak.upgrade().unwrap();
+    assert_eq!(strong.call(TestAdd1Message(1)).await.unwrap(), 2);
+}
+
+#[crate::test]
+async fn test_typed_to_erased() {
+    let actor = TestActor.spawn().await.unwrap();
+    assert_eq!(actor.call(TestAdd1Message(1)).await.unwrap(), 2);
+    let erased = actor.erase();
     assert_eq!(
-        strong
+        erased
             .call::<TestActor, TestAdd1Message>(TestAdd1Message(1))
             .await
             .unwrap(),
@@ -0,0 +1,191 @@
+use std::time::{Duration, Instant};
+
+use super::*;
+use crate::{
+    utils::{
+        backoff::{Backoff, BackoffState},
+        default_supervisor::{DefaultSupervisor, DefaultSupervisorRestartStrategy},
+    },
+    ActorRestart, ChildSpec, SetBackoff, StartChild, Supervise,
+};
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+#[test]
+fn test_backoff_delay() {
+    assert_eq!(Backoff::Constant(ms(10)).delay(5), ms(10));
+    let linear = Backoff::Linear {
+        initial: ms(10),
+        step: ms(20),
+        max: ms(60),
+    };
+    assert_eq!(
+        (0..5).map(|n| linear.delay(n)).collect::<Vec<_>>(),
+        vec![ms(10), ms(30), ms(50), ms(60), ms(60)]
+    );
+    let exponential = Backoff::Exponential {
+        initial: ms(10),
+        max: ms(100),
+        jitter: 0.0,
+    };
+    assert_eq!(
+        (0..5).map(|n| exponential.delay(n)).collect::<Vec<_>>(),
+        vec![ms(10), ms(20), ms(40), ms(80), ms(100)]
+    );
+    assert_eq!(exponential.delay(u32::MAX), ms(100));
+    let jittered = Backoff::Exponential {
+        initial: ms(10),
+        max: ms(100),
+        jitter: 0.5,
+    };
+    for n in 0..5 {
+        let delay = jittered.delay(n);
+        assert!(delay <= exponential.delay(n) && delay >= exponential.delay(n) / 2);
+    }
+}
+
+#[crate::test]
+async fn test_backoff_reset() {
+    let backoff = Backoff::Exponential {
+        initial: ms(1),
+        max: ms(100),
+        jitter: 0.0,
+    };
+    let mut state = BackoffState::default();
+    assert_eq!(state.next_delay(&backoff, ms(50)), ms(1));
+    assert_eq!(state.next_delay(&backoff, ms(50)), ms(2));
+    assert_eq!(state.next_delay(&backoff, ms(50)), ms(4));
+    tokio::time::sleep(ms(100)).await;
+    assert_eq!(state.next_delay(&backoff, ms(50)), ms(1));
+}
+
+#[derive(Default)]
+struct Fragile;
+impl Actor for Fragile {}
+impl ActorRestart for Fragile {}
+
+#[crate::message(result = "()")]
+struct Break;
+
+#[async_trait::async_trait]
+impl Handler<Break> for Fragile {
+    async fn handle(&self, _ctx: &Context, _msg: Break) -> anyhow::Result<()> {
+        Err(anyhow::anyhow!("broken"))
+    }
+}
+
+#[crate::message(result = "()")]
+struct Ping;
+
+#[async_trait::async_trait]
+impl Handler<Ping> for Fragile {
+    async fn handle(&self, _ctx: &Context, _msg: Ping) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+#[crate::test]
+async fn test_backoff_per_actor() {
+    let supervisor = DefaultSupervisor::new(DefaultSupervisorRestartStrategy::OneForOne)
+        .with_backoff(Backoff::Constant(Duration::ZERO))
+        .spawn()
+        .await
+        .unwrap();
+    let supervise_proxy = supervisor.proxy::<Supervise>().await;
+    let mut actors = vec![];
+    for _ in 0..2 {
+        actors.push(
+            Fragile
+                .spawn_supervisable()
+                .await
+                .unwrap()
+                .chain_link_to_supervisor(&supervise_proxy)
+                .await
+                .unwrap(),
+        );
+    }
+    supervisor
+        .call(SetBackoff(actors[1].id, Backoff::Constant(ms(300))))
+        .await
+        .unwrap();
+    assert!(
+        supervisor
+            .call(SetBackoff(u64::MAX, Backoff::default()))
+            .await
+            .is_err()
+    );
+    let mut elapsed = vec![];
+    for actor in &actors {
+        let start = Instant::now();
+        let _ = actor.call(Break).await;
+        // handled once the actor is restarted
+        actor.call(Ping).await.unwrap();
+        elapsed.push(start.elapsed());
+    }
+    assert!(elapsed[0] < ms(200));
+    assert!(elapsed[1] >= ms(300));
+}
+
+#[crate::test]
+async fn test_backoff_does_not_block_other_actors() {
+    let supervisor = DefaultSupervisor::new(DefaultSupervisorRestartStrategy::OneForOne)
+        .with_backoff(Backoff::Constant(Duration::ZERO))
+        .spawn()
+        .await
+        .unwrap();
+    let supervise_proxy = supervisor.proxy::<Supervise>().await;
+    let mut actors = vec![];
+    for _ in 0..2 {
+        actors.push(
+            Fragile
+                .spawn_supervisable()
+                .await
+                .unwrap()
+                .chain_link_to_supervisor(&supervise_proxy)
+                .await
+                .unwrap(),
+        );
+    }
+    supervisor
+        .call(SetBackoff(actors[0].id, Backoff::Constant(ms(1000))))
+        .await
+        .unwrap();
+    let _ = actors[0].call(Break).await;
+    tokio::time::sleep(ms(50)).await;
+    let start = Instant::now();
+    let _ = actors[1].call(Break).await;
+    actors[1].call(Ping).await.unwrap();
+    assert!(start.elapsed() < ms(200));
+    // the supervisor still handles messages
+    let start = Instant::now();
+    supervisor
+        .call(SetBackoff(actors[1].id, Backoff::default()))
+        .await
+        .unwrap();
+    assert!(start.elapsed() < ms(200));
+    for actor in actors {
+        actor.stop(Ok(()));
+    }
+}
+
+#[crate::test]
+async fn test_child_spec_backoff() {
+    let supervisor = DefaultSupervisor::new(DefaultSupervisorRestartStrategy::OneForOne)
+        .with_backoff(Backoff::Constant(Duration::ZERO))
+        .spawn()
+        .await
+        .unwrap();
+    let child = supervisor
+        .call(StartChild(
+            ChildSpec::new(|| Fragile).with_backoff(Backoff::Constant(ms(300))),
+        ))
+        .await
+        .unwrap();
+    let start = Instant::now();
+    let _ = child.call::<Fragile, _>(Break).await;
+    child.call::<Fragile, _>(Ping).await.unwrap();
+    assert!(start.elapsed() >= ms(300));
+    supervisor.stop(Ok(()));
+}
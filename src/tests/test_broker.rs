@@ -38,9 +38,7 @@ async fn test_all_message_arrive() {
         .spawn()
         .await
         .unwrap();
-    let subscribe_proxy = broker
-        .proxy::<DefaultBroker<TestAdd1Message>, Subscribe<TestAdd1Message>>()
-        .await;
+    let subscribe_proxy = broker.proxy::<Subscribe<TestAdd1Message>>().await;
     let a1 = TestState::default().spawn().await.unwrap();
     let a2 = TestState::default().spawn().await.unwrap();
     let a3 = TestState::default().spawn().await.unwrap();
@@ -58,14 +56,9 @@ async fn test_all_message_arrive() {
         .await
         .unwrap();
 
-    broker
-        .call::<DefaultBroker<TestAdd1Message>, Publish<TestAdd1Message>>(Publish(TestAdd1Message(
-            1,
-        )))
-        .await
-        .unwrap();
+    broker.call(Publish(TestAdd1Message(1))).await.unwrap();
 
-    assert_eq!(a1.call::<TestState, Get>(Get).await.unwrap(), 1);
-    assert_eq!(a2.call::<TestState, Get>(Get).await.unwrap(), 1);
-    assert_eq!(a3.call::<TestState, Get>(Get).await.unwrap(), 1);
+    assert_eq!(a1.call(Get).await.unwrap(), 1);
+    assert_eq!(a2.call(Get).await.unwrap(), 1);
+    assert_eq!(a3.call(Get).await.unwrap(), 1);
 }
@@ -0,0 +1,73 @@
+use std::sync::{atomic::AtomicBool, Arc};
+
+use super::*;
+use crate::{
+    utils::default_supervisor::DefaultSupervisor, ActorRestart, ActorSystem, HandlerPanic,
+    Supervise,
+};
+
+#[derive(Default)]
+struct Fragile(Arc<AtomicBool>);
+
+#[async_trait::async_trait]
+impl Actor for Fragile {
+    async fn on_stop(&self, _ctx: &Context) {
+        self.0.store(true, std::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+impl ActorRestart for Fragile {}
+
+#[crate::message(result = "()")]
+struct Explode;
+
+#[async_trait::async_trait]
+impl Handler<Explode> for Fragile {
+    async fn handle(&self, _ctx: &Context, _msg: Explode) -> anyhow::Result<()> {
+        panic!("boom");
+    }
+}
+
+#[async_trait::async_trait]
+impl Handler<TestAdd1Message> for Fragile {
+    async fn handle(&self, _ctx: &Context, msg: TestAdd1Message) -> anyhow::Result<i32> {
+        Ok(msg.0 + 1)
+    }
+}
+
+#[crate::test]
+async fn test_panic_is_restarted_by_supervisor() {
+    let supervisor = DefaultSupervisor::new(
+        xtor::utils::default_supervisor::DefaultSupervisorRestartStrategy::OneForOne,
+    )
+    .spawn()
+    .await
+    .unwrap();
+    let actor = Fragile::default()
+        .spawn_supervisable()
+        .await
+        .unwrap()
+        .chain_link_to_supervisor(&supervisor.proxy::<Supervise>().await)
+        .await
+        .unwrap();
+    let err = actor.call(Explode).await.unwrap_err();
+    assert_eq!(err.downcast_ref::<HandlerPanic>().unwrap().0, "boom");
+    assert_eq!(actor.call(TestAdd1Message(1)).await.unwrap(), 2);
+    actor.stop(Ok(()));
+    supervisor.stop(Ok(()));
+}
+
+#[crate::test]
+async fn test_panic_stops_actor_with_cleanup() {
+    let stopped = Arc::new(AtomicBool::new(false));
+    let actor = Fragile(stopped.clone()).spawn().await.unwrap();
+    let err = actor.call(Explode).await.unwrap_err();
+    assert!(err.is::<HandlerPanic>());
+    assert!(matches!(
+        actor.await_stop().await,
+        crate::ExitReason::Panicked(_)
+    ));
+    assert!(stopped.load(std::sync::atomic::Ordering::SeqCst));
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    assert!(!ActorSystem::global().is_running(actor.id));
+}
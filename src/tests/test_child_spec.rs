@@ -0,0 +1,269 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use super::*;
+use crate::{
+    utils::default_supervisor::{DefaultSupervisor, DefaultSupervisorRestartStrategy},
+    ActorRestart, ActorSystem, Addr, ChildCount, ChildRestart, ChildSpec, ChildStatus,
+    CountChildren, DeleteChild, ExitReason, RestartChild, StartChild, Supervise, TerminateChild,
+    WhichChildren,
+};
+
+struct Worker {
+    stuck_on_stop: bool,
+}
+
+#[async_trait::async_trait]
+impl Actor for Worker {
+    async fn on_stop(&self, _ctx: &Context) {
+        if self.stuck_on_stop {
+            futures::future::pending::<()>().await;
+        }
+    }
+}
+
+impl ActorRestart for Worker {}
+
+#[crate::message(result = "()")]
+struct Crash;
+
+#[async_trait::async_trait]
+impl Handler<Crash> for Worker {
+    async fn handle(&self, _ctx: &Context, _msg: Crash) -> anyhow::Result<()> {
+        Err(anyhow::anyhow!("crash"))
+    }
+}
+
+/// the spec of a worker and the number of instances it built
+fn worker_spec(restart: ChildRestart) -> (ChildSpec, Arc<AtomicUsize>) {
+    let built = Arc::new(AtomicUsize::new(0));
+    let b = built.clone();
+    let spec = ChildSpec::new(move || {
+        b.fetch_add(1, Ordering::SeqCst);
+        Worker {
+            stuck_on_stop: false,
+        }
+    })
+    .with_restart(restart);
+    (spec, built)
+}
+
+async fn supervisor() -> Addr<DefaultSupervisor> {
+    DefaultSupervisor::new(DefaultSupervisorRestartStrategy::OneForOne)
+        .spawn()
+        .await
+        .unwrap()
+}
+
+#[crate::test]
+async fn test_child_spec_permanent() {
+    let supervisor = supervisor().await;
+    let (spec, built) = worker_spec(ChildRestart::Permanent);
+    let child = supervisor.call(StartChild(spec)).await.unwrap();
+    let _ = child.call::<Worker, _>(Crash).await;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(!child.is_stopped().await);
+    assert_eq!(built.load(Ordering::SeqCst), 2);
+    // started again after a normal exit
+    child.clone().stop(Ok(()));
+    assert!(child.await_stop().await.is_normal());
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(built.load(Ordering::SeqCst), 3);
+}
+
+#[crate::test]
+async fn test_child_spec_transient() {
+    let supervisor = supervisor().await;
+    let (spec, built) = worker_spec(ChildRestart::Transient);
+    let child = supervisor.call(StartChild(spec)).await.unwrap();
+    let _ = child.call::<Worker, _>(Crash).await;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(!child.is_stopped().await);
+    assert_eq!(built.load(Ordering::SeqCst), 2);
+    child.clone().stop(Ok(()));
+    child.await_stop().await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(built.load(Ordering::SeqCst), 2);
+}
+
+#[crate::test]
+async fn test_child_spec_temporary() {
+    let supervisor = supervisor().await;
+    let (spec, built) = worker_spec(ChildRestart::Temporary);
+    let child = supervisor.call(StartChild(spec)).await.unwrap();
+    let _ = child.call::<Worker, _>(Crash).await;
+    assert!(matches!(
+        child.await_stop().await,
+        ExitReason::SupervisorGaveUp(_)
+    ));
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(built.load(Ordering::SeqCst), 1);
+}
+
+#[crate::test]
+async fn test_child_spec_shutdown() {
+    let supervisor = supervisor().await;
+    let stuck = ChildSpec::new(|| Worker {
+        stuck_on_stop: true,
+    })
+    .with_shutdown(Duration::from_millis(100));
+    let (spec, _) = worker_spec(ChildRestart::Permanent);
+    let stuck = supervisor.call(StartChild(stuck)).await.unwrap();
+    let child = supervisor.call(StartChild(spec)).await.unwrap();
+    let start = std::time::Instant::now();
+    supervisor.clone().stop(Ok(()));
+    supervisor.await_stop().await;
+    assert!(start.elapsed() >= Duration::from_millis(100));
+    assert!(child.await_stop().await.is_normal());
+    assert!(matches!(stuck.await_stop().await, ExitReason::Killed));
+}
+
+#[crate::test]
+async fn test_which_children() {
+    let supervisor = supervisor().await;
+    let (spec, _) = worker_spec(ChildRestart::Permanent);
+    let first = supervisor.call(StartChild(spec.clone())).await.unwrap();
+    first.set_name("first").await;
+    let second = supervisor.call(StartChild(spec)).await.unwrap();
+    let linked = Worker {
+        stuck_on_stop: false,
+    }
+    .spawn_supervisable()
+    .await
+    .unwrap()
+    .chain_link_to_supervisor(&supervisor.proxy::<Supervise>().await)
+    .await
+    .unwrap();
+    let _ = first.call::<Worker, _>(Crash).await;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let children = supervisor.call(WhichChildren).await.unwrap();
+    assert_eq!(
+        children.iter().map(|child| child.id).collect::<Vec<_>>(),
+        vec![first.id, second.id, linked.id]
+    );
+    assert_eq!(children[0].name.as_deref(), Some("first"));
+    assert!(
+        children
+            .iter()
+            .all(|child| child.status == ChildStatus::Running)
+    );
+    assert_eq!(
+        children
+            .iter()
+            .map(|child| child.restarts)
+            .collect::<Vec<_>>(),
+        vec![1, 0, 0]
+    );
+    assert_eq!(
+        supervisor.call(CountChildren).await.unwrap(),
+        ChildCount {
+            total: 3,
+            running: 3
+        }
+    );
+}
+
+#[crate::test]
+async fn test_exited_supervised_actor_is_forgotten() {
+    let supervisor = supervisor().await;
+    let (spec, _) = worker_spec(ChildRestart::Permanent);
+    let child = supervisor.call(StartChild(spec)).await.unwrap();
+    let linked = Worker {
+        stuck_on_stop: false,
+    }
+    .spawn_supervisable()
+    .await
+    .unwrap()
+    .chain_link_to_supervisor(&supervisor.proxy::<Supervise>().await)
+    .await
+    .unwrap();
+    let l = linked.clone();
+    linked.stop(Ok(()));
+    assert!(l.await_stop().await.is_normal());
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let children = supervisor.call(WhichChildren).await.unwrap();
+    assert_eq!(
+        children
+            .iter()
+            .map(|child| (child.id, child.status))
+            .collect::<Vec<_>>(),
+        vec![(child.id, ChildStatus::Running)]
+    );
+    assert_eq!(
+        supervisor.call(CountChildren).await.unwrap(),
+        ChildCount {
+            total: 1,
+            running: 1
+        }
+    );
+}
+
+#[crate::test]
+async fn test_terminate_restart_delete_child() {
+    let supervisor = supervisor().await;
+    let (spec, built) = worker_spec(ChildRestart::Permanent);
+    let first = supervisor.call(StartChild(spec.clone())).await.unwrap();
+    let second = supervisor.call(StartChild(spec)).await.unwrap();
+    supervisor.call(TerminateChild(first.id)).await.unwrap();
+    assert!(first.await_stop().await.is_normal());
+    assert!(supervisor.call(TerminateChild(first.id)).await.is_err());
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    // not started again by the supervisor
+    assert_eq!(built.load(Ordering::SeqCst), 2);
+    let children = supervisor.call(WhichChildren).await.unwrap();
+    assert_eq!(children[0].status, ChildStatus::Terminated);
+    assert!(supervisor.call(DeleteChild(second.id)).await.is_err());
+
+    let first = supervisor.call(RestartChild(first.id)).await.unwrap();
+    assert!(!first.is_stopped().await);
+    assert_eq!(built.load(Ordering::SeqCst), 3);
+    let restarted = supervisor.call(RestartChild(second.id)).await.unwrap();
+    assert_eq!(restarted.id, second.id);
+    let children = supervisor.call(WhichChildren).await.unwrap();
+    assert_eq!(
+        children
+            .iter()
+            .map(|child| (child.id, child.status, child.restarts))
+            .collect::<Vec<_>>(),
+        vec![
+            (first.id, ChildStatus::Running, 1),
+            (second.id, ChildStatus::Running, 1)
+        ]
+    );
+
+    supervisor.call(TerminateChild(first.id)).await.unwrap();
+    supervisor.call(DeleteChild(first.id)).await.unwrap();
+    assert_eq!(
+        supervisor.call(CountChildren).await.unwrap(),
+        ChildCount {
+            total: 1,
+            running: 1
+        }
+    );
+    assert!(!supervisor.is_stopped().await);
+}
+
+#[crate::test]
+async fn test_permanent_child_not_started_during_shutdown() {
+    let system = ActorSystem::new();
+    let supervisor = system
+        .spawn(DefaultSupervisor::new(
+            DefaultSupervisorRestartStrategy::OneForOne,
+        ))
+        .await
+        .unwrap();
+    let (spec, built) = worker_spec(ChildRestart::Permanent);
+    let child = supervisor.call(StartChild(spec)).await.unwrap();
+    let report = system.shutdown(Duration::from_millis(500)).await;
+    assert!(report.is_clean());
+    assert_eq!(report.stopped, 2);
+    assert!(child.await_stop().await.is_normal());
+    assert_eq!(built.load(Ordering::SeqCst), 1);
+    assert_eq!(system.actor_count(), 0);
+    assert!(!system.is_shutting_down());
+}
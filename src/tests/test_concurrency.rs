@@ -0,0 +1,53 @@
+use super::*;
+use crate::ActorRunner;
+
+#[crate::test]
+async fn test_concurrent_handlers() {
+    let actor = ActorRunner::new()
+        .with_concurrency(3)
+        .run(TestActor)
+        .await
+        .unwrap();
+    let start = std::time::Instant::now();
+    let results =
+        futures::future::join_all((0..3).map(|_| actor.call(TestSleepAdd1Message(500)))).await;
+    assert!(start.elapsed() < std::time::Duration::from_millis(1000));
+    for res in results {
+        assert_eq!(res.unwrap(), 501);
+    }
+}
+
+#[crate::test]
+async fn test_concurrency_is_limited() {
+    let actor = ActorRunner::new()
+        .with_concurrency(2)
+        .run(TestActor)
+        .await
+        .unwrap();
+    let start = std::time::Instant::now();
+    let results =
+        futures::future::join_all((0..3).map(|_| actor.call(TestSleepAdd1Message(300)))).await;
+    assert!(start.elapsed() >= std::time::Duration::from_millis(600));
+    for res in results {
+        assert_eq!(res.unwrap(), 301);
+    }
+}
+
+#[crate::test]
+async fn test_stop_waits_for_handlers_in_flight() {
+    let actor = ActorRunner::new()
+        .with_concurrency(2)
+        .run(TestActor)
+        .await
+        .unwrap();
+    let res1 = actor.call_unblock(TestSleepAdd1Message(300)).await;
+    let res2 = actor.call_unblock(TestSleepAdd1Message(200)).await;
+    let a = actor.clone();
+    actor.stop(Ok(()));
+    let res3 = a.call_unblock(TestAdd1Message(1)).await;
+    assert!(a.await_stop().await.is_normal());
+    assert_eq!(res1.await.unwrap().unwrap(), 301);
+    assert_eq!(res2.await.unwrap().unwrap(), 201);
+    // messages after the stop event are not handled
+    assert!(res3.await.unwrap().unwrap_err().is::<crate::ActorStopped>());
+}
@@ -0,0 +1,81 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use super::*;
+use crate::{ActorRunner, MailboxCapacity, MailboxError};
+
+#[derive(Default)]
+struct Countdown(Arc<AtomicUsize>);
+impl Actor for Countdown {}
+
+#[crate::message(result = "()")]
+struct Count(usize);
+
+#[async_trait::async_trait]
+impl Handler<Count> for Countdown {
+    async fn handle(&self, ctx: &Context, msg: Count) -> anyhow::Result<()> {
+        self.0.fetch_add(1, Ordering::SeqCst);
+        if msg.0 > 0 {
+            ctx.notify::<Self, _, _>(Count(msg.0 - 1))?;
+        }
+        Ok(())
+    }
+}
+
+#[crate::message(result = "(u64, u64)")]
+struct WhoAmI;
+
+#[async_trait::async_trait]
+impl Handler<WhoAmI> for Countdown {
+    async fn handle(&self, ctx: &Context, _msg: WhoAmI) -> anyhow::Result<(u64, u64)> {
+        Ok((ctx.address().unwrap().id, ctx.weak_address().id))
+    }
+}
+
+#[crate::message(result = "(bool, Option<MailboxError>)")]
+struct Flood;
+
+#[async_trait::async_trait]
+impl Handler<Flood> for Countdown {
+    async fn handle(
+        &self,
+        ctx: &Context,
+        _msg: Flood,
+    ) -> anyhow::Result<(bool, Option<MailboxError>)> {
+        let first = ctx.notify::<Self, _, _>(Count(0)).is_ok();
+        let second = ctx.notify::<Self, _, _>(Count(0)).err();
+        Ok((first, second.and_then(|e| e.downcast_ref().copied())))
+    }
+}
+
+#[crate::test]
+async fn test_address() {
+    let actor = Countdown::default().spawn().await.unwrap();
+    assert_eq!(actor.call(WhoAmI).await.unwrap(), (actor.id, actor.id));
+    actor.stop(Ok(()));
+}
+
+#[crate::test]
+async fn test_notify_self() {
+    let countdown = Countdown::default();
+    let count = countdown.0.clone();
+    let actor = countdown.spawn().await.unwrap();
+    actor.call(Count(9)).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    assert_eq!(count.load(Ordering::SeqCst), 10);
+    actor.stop(Ok(()));
+}
+
+#[crate::test]
+async fn test_notify_full_mailbox() {
+    let actor = ActorRunner::with_capacity(MailboxCapacity::Bounded(1))
+        .run(Countdown::default())
+        .await
+        .unwrap();
+    let (first, second) = actor.call(Flood).await.unwrap();
+    assert!(first);
+    assert_eq!(second, Some(MailboxError::Full));
+    actor.stop(Ok(()));
+}
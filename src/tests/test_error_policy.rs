@@ -0,0 +1,92 @@
+use super::*;
+use crate::{utils::default_supervisor::DefaultSupervisor, ActorRunner, ErrorPolicy, Supervise};
+
+#[crate::message(result = "()", error_policy = "reply_only")]
+struct Invalid;
+
+#[async_trait::async_trait]
+impl Handler<Invalid> for TestActor {
+    async fn handle(&self, _ctx: &Context, _msg: Invalid) -> anyhow::Result<()> {
+        Err(anyhow::anyhow!("invalid"))
+    }
+}
+
+impl crate::ActorRestart for TestActor {}
+
+#[crate::test]
+async fn test_reply_only_actor_keeps_running() {
+    let actor = ActorRunner::new()
+        .with_error_policy(ErrorPolicy::ReplyOnly)
+        .run(TestActor)
+        .await
+        .unwrap();
+    let err = actor
+        .call::<TestResultMessage<i32>, _>(TestResultMessage(Err(anyhow::anyhow!("error"))))
+        .await
+        .unwrap_err();
+    assert_eq!(err.to_string(), "error");
+    assert!(!actor.is_stopped().await);
+    assert_eq!(actor.call(TestAdd1Message(1)).await.unwrap(), 2);
+}
+
+#[crate::test]
+async fn test_reply_only_message_overrides_actor() {
+    let actor = TestActor.spawn().await.unwrap();
+    let err = actor.call(Invalid).await.unwrap_err();
+    assert_eq!(err.to_string(), "invalid");
+    assert!(!actor.is_stopped().await);
+    assert_eq!(actor.call(TestAdd1Message(1)).await.unwrap(), 2);
+}
+
+#[crate::test]
+async fn test_stop_policy_skips_supervisor() {
+    let supervisor = DefaultSupervisor::new(
+        xtor::utils::default_supervisor::DefaultSupervisorRestartStrategy::OneForOne,
+    )
+    .spawn()
+    .await
+    .unwrap();
+    let actor = ActorRunner::new()
+        .with_error_policy(ErrorPolicy::Stop)
+        .supervised_run(TestActor)
+        .await
+        .unwrap()
+        .chain_link_to_supervisor(&supervisor.proxy::<Supervise>().await)
+        .await
+        .unwrap();
+    let err = actor
+        .call::<TestResultMessage<i32>, _>(TestResultMessage(Err(anyhow::anyhow!("error"))))
+        .await
+        .unwrap_err();
+    assert_eq!(err.to_string(), "error");
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    assert!(actor.is_stopped().await);
+    supervisor.stop(Ok(()));
+}
+
+#[crate::test]
+async fn test_escalate_policy_fails_supervisor() {
+    let supervisor = DefaultSupervisor::new(
+        xtor::utils::default_supervisor::DefaultSupervisorRestartStrategy::OneForOne,
+    )
+    .spawn()
+    .await
+    .unwrap();
+    let actor = ActorRunner::new()
+        .with_error_policy(ErrorPolicy::Escalate)
+        .supervised_run(TestActor)
+        .await
+        .unwrap()
+        .chain_link_to_supervisor(&supervisor.proxy::<Supervise>().await)
+        .await
+        .unwrap();
+    assert!(
+        actor
+            .call::<TestResultMessage<i32>, _>(TestResultMessage(Err(anyhow::anyhow!("error"))))
+            .await
+            .is_err()
+    );
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    assert!(actor.is_stopped().await);
+    assert!(supervisor.is_stopped().await);
+}
@@ -0,0 +1,76 @@
+use super::*;
+use crate::{ActorRunner, ErrorPolicy, ExitReason, Restart};
+
+#[crate::test]
+async fn test_exit_reason_normal() {
+    let actor = TestActor.spawn().await.unwrap();
+    let weak = actor.downgrade();
+    assert!(actor.exit_reason().is_none());
+    actor.stop(Ok(()));
+    assert!(weak.await_stop().await.is_normal());
+    assert!(weak.exit_reason().unwrap().is_normal());
+}
+
+#[crate::test]
+async fn test_exit_reason_stopped() {
+    let actor = TestActor.spawn().await.unwrap();
+    let a = actor.clone();
+    actor.stop(Err(anyhow::anyhow!("bye")));
+    match a.await_stop().await {
+        ExitReason::Stopped(err) => assert_eq!(err.to_string(), "bye"),
+        reason => panic!("unexpected exit reason {}", reason),
+    }
+}
+
+#[crate::test]
+async fn test_exit_reason_handler_error() {
+    let actor = TestActor.spawn().await.unwrap();
+    assert!(
+        actor
+            .call::<TestResultMessage<i32>, _>(TestResultMessage(Err(anyhow::anyhow!("error"))))
+            .await
+            .is_err()
+    );
+    match actor.await_stop().await {
+        ExitReason::HandlerError(err) => assert_eq!(err.to_string(), "error"),
+        reason => panic!("unexpected exit reason {}", reason),
+    }
+    assert!(matches!(
+        actor.exit_reason(),
+        Some(ExitReason::HandlerError(_))
+    ));
+}
+
+struct Quitter;
+impl Actor for Quitter {}
+
+#[async_trait::async_trait]
+impl Handler<Restart> for Quitter {
+    async fn handle(&self, _ctx: &Context, _msg: Restart) -> anyhow::Result<anyhow::Result<()>> {
+        Ok(Err(anyhow::anyhow!("give up")))
+    }
+}
+
+#[crate::test]
+async fn test_exit_reason_supervisor_gave_up() {
+    let supervisor = Quitter.spawn().await.unwrap();
+    let actor = ActorRunner::new()
+        .with_error_policy(ErrorPolicy::Restart)
+        .supervised_run(TestActor)
+        .await
+        .unwrap();
+    actor
+        .add_supervisor(supervisor.proxy::<Restart>().await)
+        .await;
+    assert!(
+        actor
+            .call::<TestResultMessage<i32>, _>(TestResultMessage(Err(anyhow::anyhow!("error"))))
+            .await
+            .is_err()
+    );
+    assert!(matches!(
+        actor.await_stop().await,
+        ExitReason::SupervisorGaveUp(_)
+    ));
+    supervisor.stop(Ok(()));
+}
@@ -0,0 +1,163 @@
+use std::sync::{Arc, Mutex};
+
+use super::*;
+use crate::{Addr, ChildFailed, ExitReason};
+
+type Log = Arc<Mutex<Vec<String>>>;
+
+struct Node {
+    name: &'static str,
+    log: Log,
+}
+
+#[async_trait::async_trait]
+impl Actor for Node {
+    async fn on_stop(&self, _ctx: &Context) {
+        self.log.lock().unwrap().push(format!("stop {}", self.name));
+    }
+}
+
+#[async_trait::async_trait]
+impl Handler<ChildFailed> for Node {
+    async fn handle(&self, _ctx: &Context, msg: ChildFailed) -> anyhow::Result<()> {
+        self.log
+            .lock()
+            .unwrap()
+            .push(format!("child {} failed: {}", msg.id, msg.reason));
+        Ok(())
+    }
+}
+
+#[crate::message(result = "Addr")]
+struct SpawnChild(&'static str);
+
+#[async_trait::async_trait]
+impl Handler<SpawnChild> for Node {
+    async fn handle(&self, ctx: &Context, msg: SpawnChild) -> anyhow::Result<Addr> {
+        let child = Node {
+            name: msg.0,
+            log: self.log.clone(),
+        };
+        Ok(ctx.spawn_child::<Self, _>(child).await?.erase())
+    }
+}
+
+#[crate::message(result = "(Option<u64>, Vec<u64>)")]
+struct Family;
+
+#[async_trait::async_trait]
+impl Handler<Family> for Node {
+    async fn handle(&self, ctx: &Context, _msg: Family) -> anyhow::Result<(Option<u64>, Vec<u64>)> {
+        Ok((
+            ctx.parent().map(|parent| parent.id),
+            ctx.children().iter().map(|child| child.id).collect(),
+        ))
+    }
+}
+
+#[crate::test]
+async fn test_children_stop_before_parent() {
+    let log = Log::default();
+    let parent = Node {
+        name: "parent",
+        log: log.clone(),
+    }
+    .spawn()
+    .await
+    .unwrap();
+    let first = parent.call(SpawnChild("first")).await.unwrap();
+    let second = parent.call(SpawnChild("second")).await.unwrap();
+    assert_eq!(
+        parent.call(Family).await.unwrap(),
+        (None, vec![first.id, second.id])
+    );
+    assert_eq!(
+        first.call::<Node, Family>(Family).await.unwrap(),
+        (Some(parent.id), vec![])
+    );
+    let p = parent.clone();
+    parent.stop(Ok(()));
+    assert!(p.await_stop().await.is_normal());
+    assert!(first.is_stopped().await);
+    assert!(second.is_stopped().await);
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec!["stop second", "stop first", "stop parent"]
+    );
+}
+
+#[crate::test]
+async fn test_child_failure_is_reported() {
+    let log = Log::default();
+    let parent = Node {
+        name: "parent",
+        log: log.clone(),
+    }
+    .spawn()
+    .await
+    .unwrap();
+    let child = parent.call(SpawnChild("child")).await.unwrap();
+    child.clone().stop(Err(anyhow::anyhow!("crash")));
+    assert!(matches!(child.await_stop().await, ExitReason::Stopped(_)));
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    assert_eq!(parent.call(Family).await.unwrap(), (None, vec![]));
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![
+            "stop child".to_string(),
+            format!("child {} failed: stopped: crash", child.id)
+        ]
+    );
+    parent.stop(Ok(()));
+}
+
+struct Stuck;
+
+#[async_trait::async_trait]
+impl Actor for Stuck {
+    async fn on_stop(&self, _ctx: &Context) {
+        futures::future::pending::<()>().await;
+    }
+}
+
+#[crate::message(result = "Addr")]
+struct SpawnStuck;
+
+#[async_trait::async_trait]
+impl Handler<SpawnStuck> for Node {
+    async fn handle(&self, ctx: &Context, _msg: SpawnStuck) -> anyhow::Result<Addr> {
+        let runner = ctx
+            .system()
+            .runner()
+            .with_shutdown_timeout(std::time::Duration::from_millis(100));
+        Ok(ctx
+            .spawn_child_with::<Self, _>(runner, Stuck)
+            .await?
+            .erase())
+    }
+}
+
+#[crate::test]
+async fn test_stuck_child_is_aborted() {
+    let log = Log::default();
+    let parent = Node {
+        name: "parent",
+        log: log.clone(),
+    }
+    .spawn()
+    .await
+    .unwrap();
+    let child = parent.call(SpawnStuck).await.unwrap();
+    let p = parent.clone();
+    let start = std::time::Instant::now();
+    parent.stop(Ok(()));
+    assert!(
+        tokio::time::timeout(std::time::Duration::from_secs(1), p.await_stop())
+            .await
+            .unwrap()
+            .is_normal()
+    );
+    assert!(start.elapsed() < std::time::Duration::from_millis(500));
+    assert!(!child.system().is_running(child.id));
+    assert_eq!(*log.lock().unwrap(), vec!["stop parent"]);
+}
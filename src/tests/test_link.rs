@@ -0,0 +1,130 @@
+use std::sync::Mutex;
+
+use super::*;
+use crate::{
+    utils::default_supervisor::DefaultSupervisor, ActorRunner, Down, Exit, ExitReason, Supervise,
+};
+
+#[derive(Default)]
+struct Watcher(Mutex<Vec<(u64, ExitReason)>>);
+impl Actor for Watcher {}
+
+#[async_trait::async_trait]
+impl Handler<Down> for Watcher {
+    async fn handle(&self, _ctx: &Context, msg: Down) -> anyhow::Result<()> {
+        self.0.lock().unwrap().push((msg.id, msg.reason));
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl Handler<Exit> for Watcher {
+    async fn handle(&self, _ctx: &Context, msg: Exit) -> anyhow::Result<()> {
+        self.0.lock().unwrap().push((msg.id, msg.reason));
+        Ok(())
+    }
+}
+
+#[crate::message(result = "Vec<(u64, ExitReason)>")]
+struct Received;
+
+#[async_trait::async_trait]
+impl Handler<Received> for Watcher {
+    async fn handle(
+        &self,
+        _ctx: &Context,
+        _msg: Received,
+    ) -> anyhow::Result<Vec<(u64, ExitReason)>> {
+        Ok(self.0.lock().unwrap().clone())
+    }
+}
+
+async fn fail(actor: &crate::Addr<TestActor>) {
+    let _ = actor
+        .call::<TestResultMessage<i32>, _>(TestResultMessage(Err(anyhow::anyhow!("error"))))
+        .await;
+}
+
+#[crate::test]
+async fn test_monitor_delivers_down() {
+    let watcher = Watcher::default().spawn().await.unwrap();
+    let target = TestActor.spawn().await.unwrap();
+    target.monitor(&watcher);
+    fail(&target).await;
+    target.await_stop().await;
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    let received = watcher.call(Received).await.unwrap();
+    assert_eq!(received.len(), 1);
+    assert_eq!(received[0].0, target.id);
+    assert!(matches!(received[0].1, ExitReason::HandlerError(_)));
+    watcher.stop(Ok(()));
+}
+
+#[crate::test]
+async fn test_link_stops_peer_on_failure() {
+    let a = TestActor.spawn().await.unwrap();
+    let b = TestActor.spawn().await.unwrap();
+    a.link(&b);
+    fail(&a).await;
+    match b.await_stop().await {
+        ExitReason::Linked(id, reason) => {
+            assert_eq!(id, a.id);
+            assert!(matches!(*reason, ExitReason::HandlerError(_)));
+        }
+        reason => panic!("unexpected exit reason {}", reason),
+    }
+}
+
+#[crate::test]
+async fn test_link_ignores_normal_exit() {
+    let a = TestActor.spawn().await.unwrap();
+    let b = TestActor.spawn().await.unwrap();
+    a.link(&b);
+    a.clone().stop(Ok(()));
+    a.await_stop().await;
+    assert!(!b.is_stopped().await);
+    b.stop(Ok(()));
+}
+
+#[crate::test]
+async fn test_link_trap_exit() {
+    let a = TestActor.spawn().await.unwrap();
+    let b = ActorRunner::new()
+        .with_trap_exit::<Watcher>()
+        .run(Watcher::default())
+        .await
+        .unwrap();
+    a.link(&b);
+    fail(&a).await;
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    assert!(!b.is_stopped().await);
+    let received = b.call(Received).await.unwrap();
+    assert_eq!(received.len(), 1);
+    assert_eq!(received[0].0, a.id);
+    b.stop(Ok(()));
+}
+
+#[crate::test]
+async fn test_link_supervised_actor_is_restarted() {
+    let supervisor = DefaultSupervisor::new(
+        xtor::utils::default_supervisor::DefaultSupervisorRestartStrategy::OneForOne,
+    )
+    .spawn()
+    .await
+    .unwrap();
+    let supervised = TestActor
+        .spawn_supervisable()
+        .await
+        .unwrap()
+        .chain_link_to_supervisor(&supervisor.proxy::<Supervise>().await)
+        .await
+        .unwrap();
+    let a = TestActor.spawn().await.unwrap();
+    a.link(&supervised);
+    fail(&a).await;
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    assert!(!supervised.is_stopped().await);
+    assert_eq!(supervised.call(TestAdd1Message(1)).await.unwrap(), 2);
+    supervised.stop(Ok(()));
+    supervisor.stop(Ok(()));
+}
@@ -0,0 +1,80 @@
+use super::*;
+use crate::{ActorRunner, MailboxCapacity, MailboxError};
+
+#[crate::test]
+async fn test_bounded_mailbox_try_call_full() {
+    let actor = ActorRunner::with_capacity(MailboxCapacity::Bounded(1))
+        .run(TestActor)
+        .await
+        .unwrap();
+    // first message is taken out of the mailbox and handled
+    let res1 = actor.call_unblock(TestSleepAdd1Message(500)).await;
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    // second message takes the only slot
+    let res2 = actor.call_unblock(TestSleepAdd1Message(0)).await;
+    let err = actor.try_call(TestAdd1Message(1)).await.unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<MailboxError>(),
+        Some(&MailboxError::Full)
+    );
+    assert_eq!(res1.await.unwrap().unwrap(), 501);
+    assert_eq!(res2.await.unwrap().unwrap(), 1);
+    assert_eq!(actor.try_call(TestAdd1Message(1)).await.unwrap(), 2);
+}
+
+#[crate::test]
+async fn test_bounded_mailbox_call_waits_for_space() {
+    let actor = ActorRunner::with_capacity(MailboxCapacity::Bounded(1))
+        .run(TestActor)
+        .await
+        .unwrap();
+    let proxy = actor.proxy::<TestSleepAdd1Message>().await;
+    let results =
+        futures::future::join_all((0..10).map(|i| proxy.call(TestSleepAdd1Message(i * 10)))).await;
+    for (i, res) in results.into_iter().enumerate() {
+        assert_eq!(res.unwrap(), i as i32 * 10 + 1);
+    }
+}
+
+#[crate::test]
+async fn test_bounded_proxy_try_call_closed() {
+    let actor = ActorRunner::with_capacity(MailboxCapacity::Bounded(4))
+        .run(TestActor)
+        .await
+        .unwrap();
+    let proxy = actor.proxy::<TestAdd1Message>().await;
+    let a = actor.clone();
+    actor.stop(Ok(()));
+    assert!(a.await_stop().await.is_normal());
+    assert!(proxy.try_call(TestAdd1Message(1)).await.is_err());
+}
+
+#[crate::message(result = "i32", priority = "high")]
+struct UrgentAdd1(i32);
+
+#[async_trait::async_trait]
+impl Handler<UrgentAdd1> for TestActor {
+    async fn handle(&self, _ctx: &Context, msg: UrgentAdd1) -> anyhow::Result<i32> {
+        Ok(msg.0 + 1)
+    }
+}
+
+#[crate::test]
+async fn test_bounded_mailbox_shared_by_priorities() {
+    let actor = ActorRunner::with_capacity(MailboxCapacity::Bounded(1))
+        .run(TestActor)
+        .await
+        .unwrap();
+    let res1 = actor.call_unblock(TestSleepAdd1Message(300)).await;
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    let res2 = actor.call_unblock(TestSleepAdd1Message(0)).await;
+    // the only slot is taken by a normal message
+    let err = actor.try_call(UrgentAdd1(1)).await.unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<MailboxError>(),
+        Some(&MailboxError::Full)
+    );
+    assert_eq!(res1.await.unwrap().unwrap(), 301);
+    assert_eq!(res2.await.unwrap().unwrap(), 1);
+    assert_eq!(actor.try_call(UrgentAdd1(1)).await.unwrap(), 2);
+}
@@ -4,10 +4,7 @@ use super::*;
 async fn test_single_actor_single_message_blocked() {
     let actor = TestActor.spawn().await.unwrap();
     for i in 0..100 {
-        let result = actor
-            .call::<TestActor, TestAdd1Message>(TestAdd1Message(i))
-            .await
-            .unwrap();
+        let result = actor.call(TestAdd1Message(i)).await.unwrap();
         assert_eq!(result, i + 1);
     }
 }
@@ -17,12 +14,8 @@ async fn test_two_actor_two_message_unblocked() {
     let a1 = TestActor.spawn().await.unwrap();
     let a2 = TestActor.spawn().await.unwrap();
     let start = std::time::Instant::now();
-    let res1 = a1
-        .call_unblock::<TestActor, TestSleepAdd1Message>(TestSleepAdd1Message(2000))
-        .await;
-    let res2 = a2
-        .call_unblock::<TestActor, TestSleepAdd1Message>(TestSleepAdd1Message(1000))
-        .await;
+    let res1 = a1.call_unblock(TestSleepAdd1Message(2000)).await;
+    let res2 = a2.call_unblock(TestSleepAdd1Message(1000)).await;
     let res = res2.await.unwrap().unwrap();
     assert_eq!(res, 1001);
     assert!(std::time::Instant::now() - start < std::time::Duration::from_millis(2000));
@@ -33,7 +26,7 @@ async fn test_two_actor_two_message_unblocked() {
 async fn test_single_actor_single_message_blocked_error() {
     let actor = TestActor.spawn().await.unwrap();
     let result = actor
-        .call::<TestActor, TestResultMessage<i32>>(TestResultMessage(Err(anyhow::anyhow!("error"))))
+        .call::<TestResultMessage<i32>, _>(TestResultMessage(Err(anyhow::anyhow!("error"))))
         .await;
     assert!(result.is_err());
 }
@@ -42,21 +35,20 @@ async fn test_single_actor_single_message_blocked_error() {
 async fn test_single_actor_single_message_unblocked_error() {
     let actor = TestActor.spawn().await.unwrap();
     let result = actor
-        .call_unblock::<TestActor, TestResultMessage<i32>>(TestResultMessage(Err(anyhow::anyhow!(
-            "error"
-        ))))
+        .call_unblock::<TestResultMessage<i32>, _>(TestResultMessage(Err(anyhow::anyhow!("error"))))
         .await;
     // actor will stop as soon as the message is received
     tokio::time::sleep(std::time::Duration::from_millis(100)).await;
-    // and the channel for the message is closed
-    assert!(result.await.is_err());
+    assert!(actor.is_stopped().await);
+    // and the caller still receives the error
+    assert_eq!(result.await.unwrap().unwrap_err().to_string(), "error");
 }
 
 #[crate::test]
 async fn test_single_actor_single_message_timeout_ontime() {
     let actor = TestActor.spawn().await.unwrap();
     let result = actor
-        .call_timeout::<TestActor, TestSleepAdd1Message>(
+        .call_timeout(
             TestSleepAdd1Message(1000),
             std::time::Duration::from_millis(1500),
         )
@@ -69,7 +61,7 @@ async fn test_single_actor_single_message_timeout_ontime() {
 async fn test_single_actor_single_message_timeout_delayed() {
     let actor = TestActor.spawn().await.unwrap();
     let result = actor
-        .call_timeout::<TestActor, TestSleepAdd1Message>(
+        .call_timeout(
             TestSleepAdd1Message(1500),
             std::time::Duration::from_millis(1000),
         )
@@ -82,8 +74,27 @@ async fn test_message_to_dead_actor() {
     let actor = TestActor.spawn().await.unwrap();
     let a = actor.clone();
     actor.stop(Ok(()));
-    let result = a
-        .call::<TestActor, TestAdd1Message>(TestAdd1Message(1))
-        .await;
+    let result = a.call(TestAdd1Message(1)).await;
+    assert!(result.is_err());
+}
+
+#[crate::test]
+async fn test_send_returns_without_waiting_handler() {
+    let actor = TestActor.spawn().await.unwrap();
+    let start = std::time::Instant::now();
+    actor.send(TestSleepAdd1Message(500)).await.unwrap();
+    assert!(start.elapsed() < std::time::Duration::from_millis(500));
+    // messages are still handled in order
+    assert_eq!(actor.call(TestAdd1Message(1)).await.unwrap(), 2);
+    assert!(start.elapsed() >= std::time::Duration::from_millis(500));
+}
+
+#[crate::test]
+async fn test_send_to_dead_actor() {
+    let actor = TestActor.spawn().await.unwrap();
+    let a = actor.clone();
+    actor.stop(Ok(()));
+    assert!(a.await_stop().await.is_normal());
+    let result = a.send(TestAdd1Message(1)).await;
     assert!(result.is_err());
 }
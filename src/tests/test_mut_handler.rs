@@ -0,0 +1,144 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use super::*;
+use crate::{
+    broker::{Publish, Subscribe},
+    utils::{
+        default_broker::DefaultBroker,
+        default_supervisor::{DefaultSupervisor, DefaultSupervisorRestartStrategy},
+    },
+    ActorRunner, MutHandler, Supervise,
+};
+
+/// the count and the number of restarts
+#[derive(Default)]
+struct Counter(usize, AtomicUsize);
+impl Actor for Counter {}
+
+#[async_trait::async_trait]
+impl MutHandler<TestAdd1Message> for Counter {
+    async fn handle(&mut self, _ctx: &Context, _msg: TestAdd1Message) -> anyhow::Result<i32> {
+        self.0 += 1;
+        Ok(self.0 as _)
+    }
+}
+
+#[crate::message(result = "usize")]
+struct Get;
+
+#[async_trait::async_trait]
+impl Handler<Get> for Counter {
+    async fn handle(&self, _ctx: &Context, _msg: Get) -> anyhow::Result<usize> {
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        Ok(self.0)
+    }
+}
+
+#[crate::test]
+async fn test_mut_handler_changes_state() {
+    let actor = Counter::default().spawn().await.unwrap();
+    let results = futures::future::join_all((0..10).map(|_| actor.call(TestAdd1Message(0)))).await;
+    for res in results {
+        res.unwrap();
+    }
+    assert_eq!(actor.call(Get).await.unwrap(), 10);
+}
+
+#[crate::test]
+async fn test_mut_handler_waits_for_handlers_in_flight() {
+    let actor = ActorRunner::new()
+        .with_concurrency(2)
+        .run(Counter::default())
+        .await
+        .unwrap();
+    let get1 = actor.call_unblock(Get).await;
+    let add = actor.call_unblock(TestAdd1Message(0)).await;
+    let get2 = actor.call_unblock(Get).await;
+    assert_eq!(get1.await.unwrap().unwrap(), 0);
+    assert_eq!(add.await.unwrap().unwrap(), 1);
+    assert_eq!(get2.await.unwrap().unwrap(), 1);
+}
+
+#[crate::test]
+async fn test_mut_handler_through_proxy_and_broker() {
+    let actor = Counter::default().spawn().await.unwrap();
+    let proxy = actor.proxy_mut::<TestAdd1Message>().await;
+    assert_eq!(proxy.call(TestAdd1Message(0)).await.unwrap(), 1);
+
+    let broker = DefaultBroker::<TestAdd1Message>::new()
+        .spawn()
+        .await
+        .unwrap();
+    broker
+        .call(Subscribe::from_addr_mut(&actor).await)
+        .await
+        .unwrap();
+    broker.call(Publish(TestAdd1Message(0))).await.unwrap();
+    assert_eq!(actor.call(Get).await.unwrap(), 2);
+}
+
+#[crate::message(result = "()", error_policy = "restart")]
+struct Fail;
+
+#[async_trait::async_trait]
+impl MutHandler<Fail> for Counter {
+    async fn handle(&mut self, _ctx: &Context, _msg: Fail) -> anyhow::Result<()> {
+        Err(anyhow::anyhow!("fail"))
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::ActorRestart for Counter {
+    async fn post_restart(&self, _ctx: &Context) {
+        self.1.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[crate::message(result = "usize")]
+struct Restarts;
+
+#[async_trait::async_trait]
+impl Handler<Restarts> for Counter {
+    async fn handle(&self, _ctx: &Context, _msg: Restarts) -> anyhow::Result<usize> {
+        Ok(self.1.load(Ordering::SeqCst))
+    }
+}
+
+#[crate::test]
+async fn test_mut_handler_supervised_restart() {
+    let supervisor = DefaultSupervisor::new(DefaultSupervisorRestartStrategy::OneForOne)
+        .spawn()
+        .await
+        .unwrap();
+    let actor = Counter::default()
+        .spawn_supervisable()
+        .await
+        .unwrap()
+        .chain_link_to_supervisor(&supervisor.proxy::<Supervise>().await)
+        .await
+        .unwrap();
+    assert_eq!(actor.call(TestAdd1Message(0)).await.unwrap(), 1);
+    assert_eq!(actor.call(Restarts).await.unwrap(), 0);
+    assert!(actor.call(Fail).await.is_err());
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    assert!(!actor.is_stopped().await);
+    assert_eq!(actor.call(Restarts).await.unwrap(), 1);
+    // the state is kept across the restart
+    assert_eq!(actor.call(TestAdd1Message(0)).await.unwrap(), 2);
+    supervisor.stop(Ok(()));
+}
+
+#[crate::test]
+async fn test_mut_handler_errors_when_actor_is_shared() {
+    let actor = Counter::default().spawn().await.unwrap();
+    let kept = std::sync::Arc::new(std::sync::Mutex::new(None));
+    let keep = kept.clone();
+    actor.clone().erase().exec(Box::new(move |actor, _ctx| {
+        *keep.lock().unwrap() = Some(actor);
+        Box::pin(async { Ok(()) })
+    }));
+    assert!(actor.call(TestAdd1Message(0)).await.is_err());
+    assert!(!actor.is_stopped().await);
+    kept.lock().unwrap().take();
+    assert_eq!(actor.call(TestAdd1Message(0)).await.unwrap(), 1);
+}
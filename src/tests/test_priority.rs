@@ -0,0 +1,97 @@
+use super::*;
+use crate::{MutHandler, Priority};
+
+#[derive(Default)]
+struct Recorder(Vec<i32>);
+impl Actor for Recorder {}
+
+#[crate::message(result = "()")]
+struct Record(i32);
+
+#[async_trait::async_trait]
+impl MutHandler<Record> for Recorder {
+    async fn handle(&mut self, _ctx: &Context, msg: Record) -> anyhow::Result<()> {
+        self.0.push(msg.0);
+        Ok(())
+    }
+}
+
+#[crate::message(result = "()", priority = "high")]
+struct Urgent(i32);
+
+#[async_trait::async_trait]
+impl MutHandler<Urgent> for Recorder {
+    async fn handle(&mut self, _ctx: &Context, msg: Urgent) -> anyhow::Result<()> {
+        self.0.push(-msg.0);
+        Ok(())
+    }
+}
+
+#[crate::message(result = "Vec<i32>")]
+struct Take;
+
+#[async_trait::async_trait]
+impl Handler<Take> for Recorder {
+    async fn handle(&self, _ctx: &Context, _msg: Take) -> anyhow::Result<Vec<i32>> {
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        Ok(self.0.clone())
+    }
+}
+
+#[crate::test]
+async fn test_high_priority_message_overtakes() {
+    let actor = Recorder::default().spawn().await.unwrap();
+    // keeps the actor busy while the others are queued
+    let busy = actor.call_unblock(Take).await;
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    for i in 1..=3 {
+        actor.send(Record(i)).await.unwrap();
+    }
+    actor.send(Urgent(4)).await.unwrap();
+    actor
+        .send_with_priority(Record(5), Priority::High)
+        .await
+        .unwrap();
+    assert!(busy.await.unwrap().unwrap().is_empty());
+    assert_eq!(actor.call(Take).await.unwrap(), vec![-4, 5, 1, 2, 3]);
+}
+
+#[crate::test]
+async fn test_stop_handles_queued_messages_first() {
+    let actor = TestActor.spawn().await.unwrap();
+    let busy = actor.call_unblock(TestSleepAdd1Message(200)).await;
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    let queued = actor.call_unblock(TestAdd1Message(1)).await;
+    let a = actor.clone();
+    actor.stop(Ok(()));
+    let after = a.call_unblock(TestAdd1Message(2)).await;
+    assert!(a.await_stop().await.is_normal());
+    assert_eq!(busy.await.unwrap().unwrap(), 201);
+    assert_eq!(queued.await.unwrap().unwrap(), 2);
+    assert!(
+        after
+            .await
+            .unwrap()
+            .unwrap_err()
+            .is::<crate::ActorStopped>()
+    );
+}
+
+#[crate::test]
+async fn test_stop_immediate_overtakes_queued_messages() {
+    let actor = TestActor.spawn().await.unwrap();
+    let busy = actor.call_unblock(TestSleepAdd1Message(200)).await;
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    let queued = actor.call_unblock(TestAdd1Message(1)).await;
+    let a = actor.clone();
+    actor.stop_with_mode(Ok(()), crate::StopMode::Immediate);
+    assert!(a.await_stop().await.is_normal());
+    assert_eq!(busy.await.unwrap().unwrap(), 201);
+    assert!(
+        queued
+            .await
+            .unwrap()
+            .unwrap_err()
+            .is::<crate::ActorStopped>()
+    );
+}
@@ -3,7 +3,7 @@ use super::*;
 #[crate::test]
 async fn test_single_proxy_single_message_blocked() {
     let actor = TestActor.spawn().await.unwrap();
-    let proxy = actor.proxy::<TestActor, TestAdd1Message>().await;
+    let proxy = actor.proxy::<TestAdd1Message>().await;
     for i in 0..100 {
         let result = proxy.call(TestAdd1Message(i)).await.unwrap();
         assert_eq!(result, i + 1);
@@ -14,8 +14,8 @@ async fn test_single_proxy_single_message_blocked() {
 async fn test_two_proxy_two_message_unblocked() {
     let a1 = TestActor.spawn().await.unwrap();
     let a2 = TestActor.spawn().await.unwrap();
-    let p1 = a1.proxy::<TestActor, TestSleepAdd1Message>().await;
-    let p2 = a2.proxy::<TestActor, TestSleepAdd1Message>().await;
+    let p1 = a1.proxy::<TestSleepAdd1Message>().await;
+    let p2 = a2.proxy::<TestSleepAdd1Message>().await;
     let start = std::time::Instant::now();
     let res1 = p1
         .call_unblock(TestSleepAdd1Message(2000))
@@ -36,7 +36,7 @@ async fn test_two_proxy_two_message_unblocked() {
 #[crate::test]
 async fn test_single_proxy_single_message_blocked_error() {
     let actor = TestActor.spawn().await.unwrap();
-    let proxy = actor.proxy::<TestActor, TestResultMessage<i32>>().await;
+    let proxy = actor.proxy::<TestResultMessage<i32>>().await;
     let result = proxy
         .call(TestResultMessage(Err(anyhow::anyhow!("error"))))
         .await;
@@ -46,7 +46,7 @@ async fn test_single_proxy_single_message_blocked_error() {
 #[crate::test]
 async fn test_single_proxy_single_message_unblocked_error() {
     let actor = TestActor.spawn().await.unwrap();
-    let proxy = actor.proxy::<TestActor, TestResultMessage<i32>>().await;
+    let proxy = actor.proxy::<TestResultMessage<i32>>().await;
     let result = proxy
         .call_unblock(TestResultMessage(Err(anyhow::anyhow!("error"))))
         .await
@@ -54,14 +54,15 @@ async fn test_single_proxy_single_message_unblocked_error() {
         .unwrap();
     // actor will stop as soon as the message is received
     tokio::time::sleep(std::time::Duration::from_millis(100)).await;
-    // and the channel for the message is closed
-    assert!(result.await.is_err());
+    assert!(actor.is_stopped().await);
+    // and the caller still receives the error
+    assert_eq!(result.await.unwrap().unwrap_err().to_string(), "error");
 }
 
 #[crate::test]
 async fn test_single_proxy_single_message_timeout_ontime() {
     let actor = TestActor.spawn().await.unwrap();
-    let proxy = actor.proxy::<TestActor, TestSleepAdd1Message>().await;
+    let proxy = actor.proxy::<TestSleepAdd1Message>().await;
     let result = proxy
         .call_timeout(
             TestSleepAdd1Message(1000),
@@ -75,7 +76,7 @@ async fn test_single_proxy_single_message_timeout_ontime() {
 #[crate::test]
 async fn test_single_proxy_single_message_timeout_delayed() {
     let actor = TestActor.spawn().await.unwrap();
-    let proxy = actor.proxy::<TestActor, TestSleepAdd1Message>().await;
+    let proxy = actor.proxy::<TestSleepAdd1Message>().await;
     let result = proxy
         .call_timeout(
             TestSleepAdd1Message(1500),
@@ -88,7 +89,7 @@ async fn test_single_proxy_single_message_timeout_delayed() {
 #[crate::test]
 async fn test_proxy_to_dead_actor() {
     let actor = TestActor.spawn().await.unwrap();
-    let proxy = actor.proxy::<TestActor, TestAdd1Message>().await;
+    let proxy = actor.proxy::<TestAdd1Message>().await;
     actor.stop(Ok(()));
     let result = proxy.call(TestAdd1Message(1)).await;
     assert!(result.is_err());
@@ -97,8 +98,35 @@ async fn test_proxy_to_dead_actor() {
 #[crate::test]
 async fn test_proxy_unblocked_to_dead_actor() {
     let actor = TestActor.spawn().await.unwrap();
-    let proxy = actor.proxy::<TestActor, TestAdd1Message>().await;
+    let proxy = actor.proxy::<TestAdd1Message>().await;
     actor.stop(Ok(()));
     let result = proxy.call_unblock(TestAdd1Message(1)).await.await;
     assert!(result.is_err());
 }
+
+#[crate::test]
+async fn test_proxy_send_to_dead_actor() {
+    let actor = TestActor.spawn().await.unwrap();
+    let proxy = actor.proxy::<TestAdd1Message>().await;
+    assert!(proxy.send(TestAdd1Message(1)).await.is_ok());
+    actor.stop(Ok(()));
+    let result = proxy.send(TestAdd1Message(1)).await;
+    assert!(result.is_err());
+}
+
+#[crate::test]
+async fn test_custom_proxy() {
+    let actor = TestActor.spawn().await.unwrap();
+    let inner = std::sync::Arc::new(actor.proxy::<TestAdd1Message>().await);
+    // adds 1 more to the result of the actor
+    let proxy = crate::Proxy::new(
+        actor.id,
+        Box::new(move |msg: TestAdd1Message| {
+            let inner = inner.clone();
+            Box::pin(async move { inner.call_unblock(TestAdd1Message(msg.0 + 1)).await.await })
+        }),
+    );
+    assert_eq!(proxy.call(TestAdd1Message(1)).await.unwrap(), 3);
+    assert_eq!(proxy.try_call(TestAdd1Message(1)).await.unwrap(), 3);
+    proxy.send(TestAdd1Message(1)).await.unwrap();
+}
@@ -0,0 +1,102 @@
+use super::*;
+use crate::{register, unregister, whereis, Addr, RegistryError};
+
+struct Other;
+impl Actor for Other {}
+
+#[crate::test]
+async fn test_register_and_whereis() {
+    let actor = TestActor.spawn().await.unwrap();
+    register("test_register_and_whereis", &actor).unwrap();
+    assert_eq!(
+        actor.get_name(),
+        Some("test_register_and_whereis".to_string())
+    );
+    let found: Addr = whereis("test_register_and_whereis").unwrap();
+    assert_eq!(found.id, actor.id);
+    let typed = whereis::<TestActor>("test_register_and_whereis").unwrap();
+    assert_eq!(typed.call(TestAdd1Message(1)).await.unwrap(), 2);
+    assert!(whereis::<Other>("test_register_and_whereis").is_none());
+    assert!(unregister("test_register_and_whereis"));
+    assert!(whereis::<TestActor>("test_register_and_whereis").is_none());
+    actor.stop(Ok(()));
+}
+
+#[crate::test]
+async fn test_register_duplicate_fails() {
+    let first = TestActor.spawn().await.unwrap();
+    let second = TestActor.spawn().await.unwrap();
+    register("test_register_duplicate_fails", &first).unwrap();
+    assert_eq!(
+        register("test_register_duplicate_fails", &second),
+        Err(RegistryError::NameTaken(
+            "test_register_duplicate_fails".to_string()
+        ))
+    );
+    assert_eq!(
+        register("test_register_duplicate_fails_2", &first),
+        Err(RegistryError::AlreadyRegistered(
+            "test_register_duplicate_fails".to_string()
+        ))
+    );
+    let found = whereis::<TestActor>("test_register_duplicate_fails").unwrap();
+    assert_eq!(found.id, first.id);
+    first.stop(Ok(()));
+    second.stop(Ok(()));
+}
+
+#[crate::test]
+async fn test_unregister_on_exit() {
+    let actor = TestActor.spawn().await.unwrap();
+    register("test_unregister_on_exit", &actor).unwrap();
+    let a = actor.clone();
+    actor.stop(Ok(()));
+    a.await_stop().await;
+    assert!(whereis::<TestActor>("test_unregister_on_exit").is_none());
+    let next = TestActor.spawn().await.unwrap();
+    register("test_unregister_on_exit", &next).unwrap();
+    assert_eq!(
+        whereis::<TestActor>("test_unregister_on_exit").unwrap().id,
+        next.id
+    );
+    next.stop(Ok(()));
+}
+
+#[crate::test]
+async fn test_register_concurrently_once() {
+    let actor = TestActor.spawn().await.unwrap();
+    let results = futures::future::join_all((0..8).map(|i| {
+        let actor = actor.clone();
+        tokio::spawn(
+            async move { register(format!("test_register_concurrently_once_{}", i), &actor) },
+        )
+    }))
+    .await;
+    let ok = results
+        .into_iter()
+        .filter(|res| res.as_ref().unwrap().is_ok())
+        .count();
+    assert_eq!(ok, 1);
+    actor.stop(Ok(()));
+}
+
+#[crate::test]
+async fn test_set_name_keeps_registration() {
+    let actor = TestActor.spawn().await.unwrap();
+    register("test_set_name_keeps_registration", &actor).unwrap();
+    actor.set_name("renamed").await;
+    assert_eq!(
+        whereis::<TestActor>("test_set_name_keeps_registration")
+            .unwrap()
+            .id,
+        actor.id
+    );
+    let a = actor.clone();
+    actor.stop(Ok(()));
+    a.await_stop().await;
+    assert!(
+        !crate::ActorSystem::global()
+            .registry()
+            .contains_key("test_set_name_keeps_registration")
+    );
+}
@@ -0,0 +1,187 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use super::*;
+use crate::{
+    utils::default_supervisor::{DefaultSupervisor, DefaultSupervisorRestartStrategy},
+    ActorRestart, ActorRunner, Addr, ExitReason, RestartCause, Supervise, WeakAddr,
+};
+
+#[derive(Default)]
+struct Hooked {
+    hooks: Arc<Mutex<Vec<String>>>,
+    fail_start: bool,
+}
+
+impl Hooked {
+    fn push(&self, hook: impl Into<String>) {
+        self.hooks.lock().unwrap().push(hook.into());
+    }
+}
+
+#[async_trait::async_trait]
+impl Actor for Hooked {
+    async fn on_start(&self, _ctx: &Context) -> anyhow::Result<()> {
+        self.push("on_start");
+        let restarted = self.hooks.lock().unwrap().len() > 1;
+        if self.fail_start && restarted {
+            return Err(anyhow::anyhow!("no start"));
+        }
+        Ok(())
+    }
+
+    async fn on_stop(&self, _ctx: &Context) {
+        self.push("on_stop");
+    }
+}
+
+#[async_trait::async_trait]
+impl ActorRestart for Hooked {
+    async fn pre_restart(&self, _ctx: &Context, cause: &RestartCause) {
+        self.push(format!("pre_restart: {}", cause));
+    }
+
+    async fn on_restart(&self, _addr: &WeakAddr) {
+        self.push("on_restart");
+    }
+
+    async fn post_restart(&self, _ctx: &Context) {
+        self.push("post_restart");
+    }
+}
+
+#[crate::message(result = "()")]
+struct Fail;
+
+#[async_trait::async_trait]
+impl Handler<Fail> for Hooked {
+    async fn handle(&self, _ctx: &Context, _msg: Fail) -> anyhow::Result<()> {
+        Err(anyhow::anyhow!("boom"))
+    }
+}
+
+async fn supervised(runner: ActorRunner, actor: Hooked) -> Addr<Hooked> {
+    let supervisor = DefaultSupervisor::new(DefaultSupervisorRestartStrategy::OneForOne)
+        .spawn()
+        .await
+        .unwrap();
+    runner
+        .supervised_run(actor)
+        .await
+        .unwrap()
+        .chain_link_to_supervisor(&supervisor.proxy::<Supervise>().await)
+        .await
+        .unwrap()
+}
+
+#[crate::test]
+async fn test_restart_hooks_receive_cause() {
+    let actor = Hooked::default();
+    let hooks = actor.hooks.clone();
+    let actor = supervised(ActorRunner::new(), actor).await;
+    let _ = actor.call(Fail).await;
+    // a restart waits 100ms by default
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(
+        *hooks.lock().unwrap(),
+        vec![
+            "on_start",
+            "pre_restart: failed: handler error: boom",
+            "on_restart",
+            "post_restart",
+        ]
+    );
+    actor.stop(Ok(()));
+}
+
+#[crate::test]
+async fn test_restart_lifecycle() {
+    let actor = Hooked::default();
+    let hooks = actor.hooks.clone();
+    let actor = supervised(ActorRunner::new().with_restart_lifecycle(), actor).await;
+    let _ = actor.call(Fail).await;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(
+        *hooks.lock().unwrap(),
+        vec![
+            "on_start",
+            "pre_restart: failed: handler error: boom",
+            "on_stop",
+            "on_restart",
+            "on_start",
+            "post_restart",
+        ]
+    );
+    actor.stop(Ok(()));
+}
+
+#[crate::test]
+async fn test_restart_lifecycle_start_failure() {
+    let actor = Hooked {
+        fail_start: true,
+        ..Default::default()
+    };
+    let hooks = actor.hooks.clone();
+    let actor = supervised(ActorRunner::new().with_restart_lifecycle(), actor).await;
+    let _ = actor.call(Fail).await;
+    let reason = tokio::time::timeout(Duration::from_secs(1), actor.await_stop())
+        .await
+        .unwrap();
+    assert!(matches!(reason, ExitReason::Stopped(_)));
+    // on_stop is not called twice
+    assert_eq!(
+        hooks.lock().unwrap().last().map(String::as_str),
+        Some("on_start")
+    );
+}
+
+#[derive(Default)]
+struct Counter(std::sync::atomic::AtomicUsize);
+impl Actor for Counter {}
+impl ActorRestart for Counter {}
+
+#[crate::message(result = "usize")]
+struct Incr;
+
+#[async_trait::async_trait]
+impl Handler<Incr> for Counter {
+    async fn handle(&self, _ctx: &Context, _msg: Incr) -> anyhow::Result<usize> {
+        Ok(self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1)
+    }
+}
+
+#[async_trait::async_trait]
+impl Handler<Fail> for Counter {
+    async fn handle(&self, _ctx: &Context, _msg: Fail) -> anyhow::Result<()> {
+        Err(anyhow::anyhow!("boom"))
+    }
+}
+
+#[crate::test]
+async fn test_restart_with_factory() {
+    let supervisor = DefaultSupervisor::new(DefaultSupervisorRestartStrategy::OneForOne)
+        .spawn()
+        .await
+        .unwrap();
+    let built = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let b = built.clone();
+    let actor = Counter::spawn_supervised_with(move || {
+        b.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        Counter::default()
+    })
+    .await
+    .unwrap()
+    .chain_link_to_supervisor(&supervisor.proxy::<Supervise>().await)
+    .await
+    .unwrap();
+    assert_eq!(actor.call(Incr).await.unwrap(), 1);
+    assert_eq!(actor.call(Incr).await.unwrap(), 2);
+    let _ = actor.call(Fail).await;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    // same address, new instance
+    assert_eq!(actor.call(Incr).await.unwrap(), 1);
+    assert_eq!(built.load(std::sync::atomic::Ordering::SeqCst), 2);
+    actor.stop(Ok(()));
+}
@@ -0,0 +1,88 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use super::*;
+use crate::{
+    utils::default_supervisor::{DefaultSupervisor, DefaultSupervisorRestartStrategy},
+    ActorID, ActorRestart, ActorSystem, ExitReason, Supervise,
+};
+
+struct Supervised {
+    supervisor: ActorID,
+    supervisor_alive: Arc<AtomicBool>,
+}
+
+#[async_trait::async_trait]
+impl Actor for Supervised {
+    async fn on_stop(&self, ctx: &Context) {
+        self.supervisor_alive
+            .store(ctx.system().is_running(self.supervisor), Ordering::SeqCst);
+    }
+}
+
+impl ActorRestart for Supervised {}
+
+struct Stuck;
+
+#[async_trait::async_trait]
+impl Actor for Stuck {
+    async fn on_stop(&self, _ctx: &Context) {
+        futures::future::pending::<()>().await;
+    }
+}
+
+#[crate::test]
+async fn test_shutdown_stops_all_actors() {
+    let system = ActorSystem::new();
+    let actors = futures::future::join_all((0..5).map(|_| system.spawn(TestActor))).await;
+    let report = system.shutdown(std::time::Duration::from_secs(1)).await;
+    assert!(report.is_clean());
+    assert_eq!(report.stopped, 5);
+    assert_eq!(system.actor_count(), 0);
+    for actor in actors {
+        assert!(actor.unwrap().await_stop().await.is_normal());
+    }
+    system.await_exit().await;
+}
+
+#[crate::test]
+async fn test_shutdown_stops_supervisor_last() {
+    let system = ActorSystem::new();
+    let supervisor = system
+        .spawn(DefaultSupervisor::new(
+            DefaultSupervisorRestartStrategy::OneForOne,
+        ))
+        .await
+        .unwrap();
+    let supervisor_alive = Arc::new(AtomicBool::new(false));
+    let actor = system
+        .spawn_supervisable(Supervised {
+            supervisor: supervisor.id,
+            supervisor_alive: supervisor_alive.clone(),
+        })
+        .await
+        .unwrap()
+        .chain_link_to_supervisor(&supervisor.proxy::<Supervise>().await)
+        .await
+        .unwrap();
+    let report = system.shutdown(std::time::Duration::from_secs(1)).await;
+    assert!(report.is_clean());
+    assert_eq!(report.stopped, 2);
+    assert!(actor.await_stop().await.is_normal());
+    assert!(supervisor_alive.load(Ordering::SeqCst));
+}
+
+#[crate::test]
+async fn test_shutdown_kills_after_timeout() {
+    let system = ActorSystem::new();
+    let stuck = system.spawn(Stuck).await.unwrap();
+    stuck.set_name("stuck").await;
+    let fine = system.spawn(TestActor).await.unwrap();
+    let report = system.shutdown(std::time::Duration::from_millis(100)).await;
+    assert_eq!(report.killed, vec![(stuck.id, Some("stuck".to_string()))]);
+    assert!(matches!(stuck.await_stop().await, ExitReason::Killed));
+    assert!(fine.await_stop().await.is_normal());
+    assert_eq!(system.actor_count(), 0);
+}
@@ -0,0 +1,136 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use super::*;
+use crate::{ActorStopped, ExitReason, MutHandler, StopMode};
+
+struct Stoppable(Arc<AtomicBool>);
+
+#[async_trait::async_trait]
+impl Actor for Stoppable {
+    async fn on_stop(&self, _ctx: &Context) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+#[async_trait::async_trait]
+impl Handler<TestSleepAdd1Message> for Stoppable {
+    async fn handle(&self, _ctx: &Context, msg: TestSleepAdd1Message) -> anyhow::Result<i32> {
+        tokio::time::sleep(std::time::Duration::from_millis(msg.0 as _)).await;
+        Ok(msg.0 + 1)
+    }
+}
+
+#[crate::message(result = "()")]
+struct SleepMut(u64);
+
+#[async_trait::async_trait]
+impl MutHandler<SleepMut> for Stoppable {
+    async fn handle(&mut self, _ctx: &Context, msg: SleepMut) -> anyhow::Result<()> {
+        tokio::time::sleep(std::time::Duration::from_millis(msg.0)).await;
+        Ok(())
+    }
+}
+
+#[crate::test]
+async fn test_immediate_stop_fails_queued_callers() {
+    let actor = TestActor.spawn().await.unwrap();
+    let busy = actor.call_unblock(TestSleepAdd1Message(100)).await;
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    let queued = actor.call_unblock(TestAdd1Message(1)).await;
+    let a = actor.clone();
+    actor.stop_with_mode(Ok(()), StopMode::Immediate);
+    assert!(a.await_stop().await.is_normal());
+    assert_eq!(busy.await.unwrap().unwrap(), 101);
+    assert!(queued.await.unwrap().unwrap_err().is::<ActorStopped>());
+    let err = a.call(TestAdd1Message(1)).await.unwrap_err();
+    assert!(err.is::<ActorStopped>());
+}
+
+#[crate::test]
+async fn test_drain_handles_queued_messages() {
+    let actor = TestActor.spawn().await.unwrap();
+    let mut queued = vec![];
+    for i in 0..5 {
+        queued.push(actor.call_unblock(TestSleepAdd1Message(i * 10)).await);
+    }
+    let a = actor.clone();
+    actor.stop_with_mode(Ok(()), StopMode::Drain);
+    assert!(a.await_stop().await.is_normal());
+    for (i, res) in queued.into_iter().enumerate() {
+        assert_eq!(res.await.unwrap().unwrap(), i as i32 * 10 + 1);
+    }
+}
+
+#[crate::test]
+async fn test_drain_deadline_kills_actor() {
+    let stopped = Arc::new(AtomicBool::new(false));
+    let actor = Stoppable(stopped.clone()).spawn().await.unwrap();
+    actor.set_name("stoppable").await;
+    let fast = actor.call_unblock(TestSleepAdd1Message(0)).await;
+    let slow = actor.call_unblock(TestSleepAdd1Message(10_000)).await;
+    let queued = actor.call_unblock(TestSleepAdd1Message(0)).await;
+    let a = actor.clone();
+    let start = std::time::Instant::now();
+    actor.stop_with_mode(
+        Ok(()),
+        StopMode::DrainWithDeadline(std::time::Duration::from_millis(200)),
+    );
+    assert!(matches!(a.await_stop().await, ExitReason::Killed));
+    assert!(start.elapsed() < std::time::Duration::from_secs(1));
+    assert!(stopped.load(Ordering::SeqCst));
+    assert_eq!(fast.await.unwrap().unwrap(), 1);
+    assert!(slow.await.unwrap().unwrap_err().is::<ActorStopped>());
+    assert!(queued.await.unwrap().unwrap_err().is::<ActorStopped>());
+    assert_eq!(a.get_name(), None);
+}
+
+#[crate::test]
+async fn test_kill_runs_on_stop() {
+    let stopped = Arc::new(AtomicBool::new(false));
+    let actor = Stoppable(stopped.clone()).spawn().await.unwrap();
+    let slow = actor.call_unblock(TestSleepAdd1Message(10_000)).await;
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    let a = actor.clone();
+    actor.kill();
+    assert!(matches!(a.await_stop().await, ExitReason::Killed));
+    assert!(stopped.load(Ordering::SeqCst));
+    assert!(slow.await.unwrap().unwrap_err().is::<ActorStopped>());
+}
+
+#[crate::test]
+async fn test_kill_interrupts_mut_handler() {
+    let stopped = Arc::new(AtomicBool::new(false));
+    let actor = Stoppable(stopped.clone()).spawn().await.unwrap();
+    let slow = actor.call_unblock(SleepMut(2_000)).await;
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    let a = actor.clone();
+    let start = std::time::Instant::now();
+    actor.kill();
+    assert!(matches!(a.await_stop().await, ExitReason::Killed));
+    assert!(start.elapsed() < std::time::Duration::from_millis(500));
+    assert!(stopped.load(Ordering::SeqCst));
+    assert!(slow.await.unwrap().unwrap_err().is::<ActorStopped>());
+}
+
+#[crate::test]
+async fn test_drain_deadline_while_mut_handler_waits() {
+    let actor = Stoppable(Default::default()).spawn().await.unwrap();
+    let slow = actor.call_unblock(TestSleepAdd1Message(2_000)).await;
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    // waits for the slow handler before running
+    let exclusive = actor.call_unblock(SleepMut(0)).await;
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    let a = actor.clone();
+    let start = std::time::Instant::now();
+    actor.stop_with_mode(
+        Ok(()),
+        StopMode::DrainWithDeadline(std::time::Duration::from_millis(100)),
+    );
+    assert!(matches!(a.await_stop().await, ExitReason::Killed));
+    assert!(start.elapsed() < std::time::Duration::from_millis(500));
+    assert!(slow.await.unwrap().unwrap_err().is::<ActorStopped>());
+    assert!(exclusive.await.unwrap().unwrap_err().is::<ActorStopped>());
+}
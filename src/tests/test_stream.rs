@@ -0,0 +1,190 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use futures::{stream::BoxStream, StreamExt};
+
+use super::*;
+use crate::{
+    utils::default_supervisor::{DefaultSupervisor, DefaultSupervisorRestartStrategy},
+    ActorRestart, StreamHandle, StreamHandler, StreamRestart, Supervise,
+};
+
+#[derive(Default)]
+struct Collector {
+    events: Arc<Mutex<Vec<String>>>,
+    stream: Arc<Mutex<Option<StreamHandle>>>,
+}
+
+impl Actor for Collector {}
+impl ActorRestart for Collector {}
+
+#[async_trait::async_trait]
+impl StreamHandler<u32> for Collector {
+    async fn started(&self, _ctx: &Context) {
+        self.events.lock().unwrap().push("started".into());
+    }
+
+    async fn handle(&self, _ctx: &Context, item: u32) -> anyhow::Result<()> {
+        if item == 0 {
+            return Err(anyhow::anyhow!("zero"));
+        }
+        self.events.lock().unwrap().push(item.to_string());
+        Ok(())
+    }
+
+    async fn finished(&self, _ctx: &Context) {
+        self.events.lock().unwrap().push("finished".into());
+    }
+}
+
+#[crate::message(result = "()")]
+struct Mark;
+
+#[async_trait::async_trait]
+impl Handler<Mark> for Collector {
+    async fn handle(&self, _ctx: &Context, _msg: Mark) -> anyhow::Result<()> {
+        self.events.lock().unwrap().push("mark".into());
+        Ok(())
+    }
+}
+
+#[crate::message(result = "()")]
+struct Attach(BoxStream<'static, u32>);
+
+#[async_trait::async_trait]
+impl Handler<Attach> for Collector {
+    async fn handle(&self, ctx: &Context, msg: Attach) -> anyhow::Result<()> {
+        let stream = ctx.add_stream::<Self, _, _>(msg.0);
+        *self.stream.lock().unwrap() = Some(stream);
+        Ok(())
+    }
+}
+
+#[crate::message(result = "()")]
+struct AttachWith(StreamRestart);
+
+#[async_trait::async_trait]
+impl Handler<AttachWith> for Collector {
+    async fn handle(&self, ctx: &Context, msg: AttachWith) -> anyhow::Result<()> {
+        let stream = ctx.add_stream_with::<Self, _, _, _>(
+            || futures::stream::iter([1, 0]).chain(futures::stream::pending()),
+            msg.0,
+        );
+        *self.stream.lock().unwrap() = Some(stream);
+        Ok(())
+    }
+}
+
+fn ticks() -> impl futures::Stream<Item = u32> + Send {
+    futures::stream::unfold(1, |i| async move {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        Some((i, i + 1))
+    })
+}
+
+#[crate::test]
+async fn test_stream_started_and_finished() {
+    let collector = Collector::default();
+    let events = collector.events.clone();
+    let stream = collector.stream.clone();
+    let actor = collector.spawn().await.unwrap();
+    actor
+        .call(Attach(futures::stream::iter([1, 2, 3]).boxed()))
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec!["started", "1", "2", "3", "finished"]
+    );
+    assert!(!stream.lock().unwrap().as_ref().unwrap().is_attached());
+    actor.stop(Ok(()));
+}
+
+#[crate::test]
+async fn test_stream_interleaves_with_messages() {
+    let collector = Collector::default();
+    let events = collector.events.clone();
+    let actor = collector.spawn().await.unwrap();
+    actor.call(Attach(ticks().boxed())).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(70)).await;
+    actor.call(Mark).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(70)).await;
+    let events = events.lock().unwrap().clone();
+    let mark = events.iter().position(|e| e == "mark").unwrap();
+    assert!(mark > 1 && mark < events.len() - 1);
+    actor.stop(Ok(()));
+}
+
+#[crate::test]
+async fn test_stream_cancel() {
+    let collector = Collector::default();
+    let events = collector.events.clone();
+    let stream = collector.stream.clone();
+    let actor = collector.spawn().await.unwrap();
+    actor.call(Attach(ticks().boxed())).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(70)).await;
+    let stream = stream.lock().unwrap().clone().unwrap();
+    stream.cancel();
+    assert!(!stream.is_attached());
+    let count = events.lock().unwrap().len();
+    tokio::time::sleep(Duration::from_millis(70)).await;
+    assert!(events.lock().unwrap().len() <= count + 1);
+    assert!(!events.lock().unwrap().contains(&"finished".to_string()));
+    actor.stop(Ok(()));
+}
+
+#[crate::test]
+async fn test_stream_cancelled_on_stop() {
+    let collector = Collector::default();
+    let stream = collector.stream.clone();
+    let actor = collector.spawn().await.unwrap();
+    actor.call(Attach(ticks().boxed())).await.unwrap();
+    let stream = stream.lock().unwrap().clone().unwrap();
+    assert!(stream.is_attached());
+    let a = actor.clone();
+    actor.stop(Ok(()));
+    a.await_stop().await;
+    assert!(!stream.is_attached());
+}
+
+async fn restart_with(policy: StreamRestart) -> (Vec<String>, bool) {
+    let supervisor = DefaultSupervisor::new(DefaultSupervisorRestartStrategy::OneForOne)
+        .spawn()
+        .await
+        .unwrap();
+    let collector = Collector::default();
+    let events = collector.events.clone();
+    let stream = collector.stream.clone();
+    let actor = collector
+        .spawn_supervisable()
+        .await
+        .unwrap()
+        .chain_link_to_supervisor(&supervisor.proxy::<Supervise>().await)
+        .await
+        .unwrap();
+    actor.call(AttachWith(policy)).await.unwrap();
+    // a restart waits 100ms by default
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let attached = stream.lock().unwrap().as_ref().unwrap().is_attached();
+    actor.stop(Ok(()));
+    supervisor.stop(Ok(()));
+    let events = events.lock().unwrap().clone();
+    (events, attached)
+}
+
+#[crate::test]
+async fn test_stream_restart_policy() {
+    // the item 0 fails the actor which is restarted by its supervisor
+    let (events, attached) = restart_with(StreamRestart::Resume).await;
+    assert_eq!(events, vec!["started", "1"]);
+    assert!(attached);
+    let (events, attached) = restart_with(StreamRestart::Cancel).await;
+    assert_eq!(events, vec!["started", "1"]);
+    assert!(!attached);
+    let (events, _) = restart_with(StreamRestart::Reattach).await;
+    assert!(events.len() > 2);
+    assert_eq!(events[..4], ["started", "1", "started", "1"]);
+}
@@ -53,7 +53,7 @@ async fn test_supervisor_save_one() {
     .spawn()
     .await
     .unwrap();
-    let supervise_proxy = supervisor.proxy::<DefaultSupervisor, Supervise>().await;
+    let supervise_proxy = supervisor.proxy::<Supervise>().await;
     let actor = Dummy::default()
         .spawn_supervisable()
         .await
@@ -61,9 +61,9 @@ async fn test_supervisor_save_one() {
         .chain_link_to_supervisor(&supervise_proxy)
         .await
         .unwrap();
-    let _ = actor.call::<Dummy, Die>(Die).await;
+    let _ = actor.call(Die).await;
     assert!(!actor.is_stopped().await);
-    assert!(!actor.call::<Dummy, IsAlive>(IsAlive).await.unwrap().0);
+    assert!(!actor.call(IsAlive).await.unwrap().0);
 }
 
 #[crate::test]
@@ -74,7 +74,7 @@ async fn test_supervisor_save_all() {
     .spawn()
     .await
     .unwrap();
-    let supervise_proxy = supervisor.proxy::<DefaultSupervisor, Supervise>().await;
+    let supervise_proxy = supervisor.proxy::<Supervise>().await;
     let actors = futures::future::join_all((0..10).map(|_| async {
         Dummy::default()
             .spawn_supervisable()
@@ -85,9 +85,122 @@ async fn test_supervisor_save_all() {
             .unwrap()
     }))
     .await;
-    let _ = actors.get(0).unwrap().call::<Dummy, Die>(Die).await;
+    let _ = actors.get(0).unwrap().call(Die).await;
     tokio::time::sleep(std::time::Duration::from_millis(500)).await;
     for a in actors {
-        assert_eq!(a.call::<Dummy, IsAlive>(IsAlive).await.unwrap().1, 1);
+        assert_eq!(a.call(IsAlive).await.unwrap().1, 1);
     }
 }
+
+#[crate::test]
+async fn test_supervisor_restart_intensity() {
+    let supervisor = DefaultSupervisor::new(
+        xtor::utils::default_supervisor::DefaultSupervisorRestartStrategy::OneForOne,
+    )
+    .with_restart_intensity(2, std::time::Duration::from_secs(5))
+    .spawn()
+    .await
+    .unwrap();
+    let supervise_proxy = supervisor.proxy::<Supervise>().await;
+    let (actor, sibling) = futures::join!(
+        async {
+            Dummy::default()
+                .spawn_supervisable()
+                .await
+                .unwrap()
+                .chain_link_to_supervisor(&supervise_proxy)
+                .await
+                .unwrap()
+        },
+        async {
+            Dummy::default()
+                .spawn_supervisable()
+                .await
+                .unwrap()
+                .chain_link_to_supervisor(&supervise_proxy)
+                .await
+                .unwrap()
+        }
+    );
+    for restarts in 1..=2 {
+        let _ = actor.call(Die).await;
+        tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+        assert_eq!(actor.call(IsAlive).await.unwrap(), (false, restarts));
+    }
+    let _ = actor.call(Die).await;
+    assert!(matches!(
+        actor.await_stop().await,
+        xtor::ExitReason::SupervisorGaveUp(_)
+    ));
+    assert!(sibling.await_stop().await.is_normal());
+    assert!(matches!(
+        supervisor.await_stop().await,
+        xtor::ExitReason::Stopped(_)
+    ));
+}
+
+#[crate::test]
+async fn test_supervisor_escalates_to_supervisor() {
+    let root = DefaultSupervisor::new(
+        xtor::utils::default_supervisor::DefaultSupervisorRestartStrategy::OneForOne,
+    )
+    .spawn()
+    .await
+    .unwrap();
+    let supervisor = DefaultSupervisor::new(
+        xtor::utils::default_supervisor::DefaultSupervisorRestartStrategy::OneForOne,
+    )
+    .with_restart_intensity(0, std::time::Duration::from_secs(5))
+    .spawn_supervisable()
+    .await
+    .unwrap()
+    .chain_link_to_supervisor(&root.proxy::<Supervise>().await)
+    .await
+    .unwrap();
+    let actor = Dummy::default()
+        .spawn_supervisable()
+        .await
+        .unwrap()
+        .chain_link_to_supervisor(&supervisor.proxy::<Supervise>().await)
+        .await
+        .unwrap();
+    let _ = actor.call(Die).await;
+    assert!(matches!(
+        actor.await_stop().await,
+        xtor::ExitReason::SupervisorGaveUp(_)
+    ));
+    // restarted by the root supervisor
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+    assert!(!supervisor.is_stopped().await);
+    assert!(!root.is_stopped().await);
+}
+
+#[crate::test]
+async fn test_supervisor_rest_for_one() {
+    let supervisor = DefaultSupervisor::new(
+        xtor::utils::default_supervisor::DefaultSupervisorRestartStrategy::RestForOne,
+    )
+    .spawn()
+    .await
+    .unwrap();
+    let supervise_proxy = supervisor.proxy::<Supervise>().await;
+    let mut actors = vec![];
+    for _ in 0..4 {
+        actors.push(
+            Dummy::default()
+                .spawn_supervisable()
+                .await
+                .unwrap()
+                .chain_link_to_supervisor(&supervise_proxy)
+                .await
+                .unwrap(),
+        );
+    }
+    let _ = actors[1].call(Die).await;
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    let mut restarts = vec![];
+    for a in &actors {
+        restarts.push(a.call(IsAlive).await.unwrap().1);
+    }
+    assert_eq!(restarts, vec![0, 1, 1, 1]);
+}
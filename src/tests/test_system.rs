@@ -0,0 +1,115 @@
+use super::*;
+use crate::{register, ActorSystem, ActorSystemConfig};
+
+#[crate::test]
+async fn test_systems_are_isolated() {
+    let first = ActorSystem::new();
+    let second = ActorSystem::new();
+    let a = first.spawn(TestActor).await.unwrap();
+    let b = second.spawn(TestActor).await.unwrap();
+    assert_eq!(a.id, b.id);
+    register("test_systems_are_isolated", &a).unwrap();
+    register("test_systems_are_isolated", &b).unwrap();
+    assert_eq!(b.get_name(), Some("test_systems_are_isolated".to_string()));
+    assert!(
+        ActorSystem::global()
+            .whereis::<TestActor>("test_systems_are_isolated")
+            .is_none()
+    );
+    assert_eq!(first.actor_count(), 1);
+    a.stop(Ok(()));
+    first.await_exit().await;
+    assert_eq!(first.actor_count(), 0);
+    assert!(second.is_running(b.id));
+    assert_eq!(b.call(TestAdd1Message(1)).await.unwrap(), 2);
+    b.stop(Ok(()));
+    second.await_exit().await;
+}
+
+#[crate::test]
+async fn test_system_config() {
+    let system = ActorSystem::with_config(ActorSystemConfig {
+        stop_wait_interval: std::time::Duration::from_millis(50),
+    });
+    let actor = system.spawn(TestActor).await.unwrap();
+    assert_eq!(
+        actor.system().config().stop_wait_interval,
+        std::time::Duration::from_millis(50)
+    );
+    let start = std::time::Instant::now();
+    assert!(!actor.is_stopped().await);
+    assert!(start.elapsed() >= std::time::Duration::from_millis(50));
+    actor.stop(Ok(()));
+    system.await_exit().await;
+}
+
+struct FailStart;
+
+#[async_trait::async_trait]
+impl Actor for FailStart {
+    async fn on_start(&self, ctx: &Context) -> anyhow::Result<()> {
+        ctx.address()
+            .unwrap()
+            .set_name("test_failed_start_forgets_names")
+            .await;
+        Err(anyhow::anyhow!("start failed"))
+    }
+}
+
+impl crate::ActorRestart for FailStart {}
+
+#[crate::test]
+async fn test_failed_start_forgets_names() {
+    let system = ActorSystem::new();
+    assert!(system.spawn(FailStart).await.is_err());
+    assert!(system.names().is_empty());
+    assert!(system.spawn_supervisable(FailStart).await.is_err());
+    assert!(system.names().is_empty());
+}
+
+// the statics only see the global system, not the one of `#[crate::test]`
+#[test]
+fn test_deprecated_statics_read_the_default_system() {
+    crate::block_on(deprecated_statics_read_the_default_system());
+}
+
+#[allow(deprecated)]
+async fn deprecated_statics_read_the_default_system() {
+    let actor = TestActor.spawn().await.unwrap();
+    actor
+        .set_name("test_deprecated_statics_read_the_default_system")
+        .await;
+    assert_eq!(
+        crate::ACTOR_ID_NAME.get(&actor.id).unwrap().clone(),
+        actor.get_name()
+    );
+    assert!(crate::ACTOR_ID_HANDLE.contains_key(&actor.id));
+    assert!(std::ptr::eq(
+        &*crate::utils::service::GLOBAL_SERVICE_REGISTRY,
+        ActorSystem::global().service_registry()
+    ));
+    let a = actor.clone();
+    actor.stop(Ok(()));
+    a.await_stop().await;
+    assert!(!crate::ACTOR_ID_HANDLE.contains_key(&a.id));
+}
+
+#[crate::test]
+async fn test_each_test_has_its_own_system() {
+    let actor = TestActor.spawn().await.unwrap();
+    assert!(ActorSystem::global().is_running(actor.id));
+    assert!(std::ptr::eq(
+        ActorSystem::global().service_registry(),
+        actor.system().service_registry()
+    ));
+    // a task spawned outside of the actors does not inherit the system
+    let system = actor.system().clone();
+    let global = tokio::spawn(async move {
+        std::ptr::eq(
+            ActorSystem::global().service_registry(),
+            system.service_registry(),
+        )
+    });
+    assert!(!global.await.unwrap());
+    actor.stop(Ok(()));
+}
@@ -0,0 +1,105 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use super::*;
+use crate::TimerHandle;
+
+#[derive(Default)]
+struct Ticker {
+    ticks: Arc<AtomicUsize>,
+    timer: Arc<Mutex<Option<TimerHandle>>>,
+}
+impl Actor for Ticker {}
+
+#[crate::message(result = "()")]
+struct Tick;
+
+#[async_trait::async_trait]
+impl Handler<Tick> for Ticker {
+    async fn handle(&self, _ctx: &Context, _msg: Tick) -> anyhow::Result<()> {
+        self.ticks.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+#[crate::message(result = "()")]
+struct Start(Duration);
+
+#[async_trait::async_trait]
+impl Handler<Start> for Ticker {
+    async fn handle(&self, ctx: &Context, msg: Start) -> anyhow::Result<()> {
+        let timer = ctx.run_interval::<Self, _, _, _>(msg.0, || Tick);
+        *self.timer.lock().unwrap() = Some(timer);
+        Ok(())
+    }
+}
+
+#[crate::message(result = "()")]
+struct Later(Duration);
+
+#[async_trait::async_trait]
+impl Handler<Later> for Ticker {
+    async fn handle(&self, ctx: &Context, msg: Later) -> anyhow::Result<()> {
+        ctx.notify_later::<Self, _, _>(Tick, msg.0);
+        Ok(())
+    }
+}
+
+#[crate::message(result = "()")]
+struct Cancel;
+
+#[async_trait::async_trait]
+impl Handler<Cancel> for Ticker {
+    async fn handle(&self, _ctx: &Context, _msg: Cancel) -> anyhow::Result<()> {
+        if let Some(timer) = self.timer.lock().unwrap().take() {
+            timer.cancel();
+        }
+        Ok(())
+    }
+}
+
+#[crate::test]
+async fn test_notify_later() {
+    let ticker = Ticker::default();
+    let ticks = ticker.ticks.clone();
+    let actor = ticker.spawn().await.unwrap();
+    actor.call(Later(Duration::from_millis(50))).await.unwrap();
+    assert_eq!(ticks.load(Ordering::SeqCst), 0);
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert_eq!(ticks.load(Ordering::SeqCst), 1);
+    actor.stop(Ok(()));
+}
+
+#[crate::test]
+async fn test_run_interval_and_cancel() {
+    let ticker = Ticker::default();
+    let ticks = ticker.ticks.clone();
+    let actor = ticker.spawn().await.unwrap();
+    actor.call(Start(Duration::from_millis(20))).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    actor.call(Cancel).await.unwrap();
+    let count = ticks.load(Ordering::SeqCst);
+    assert!(count >= 3);
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(ticks.load(Ordering::SeqCst), count);
+    actor.stop(Ok(()));
+}
+
+#[crate::test]
+async fn test_timers_cancelled_on_stop() {
+    let ticker = Ticker::default();
+    let timer = ticker.timer.clone();
+    let actor = ticker.spawn().await.unwrap();
+    actor.call(Start(Duration::from_millis(20))).await.unwrap();
+    let timer = timer.lock().unwrap().clone().unwrap();
+    assert!(!timer.is_cancelled());
+    let a = actor.clone();
+    actor.stop(Ok(()));
+    a.await_stop().await;
+    assert!(timer.is_cancelled());
+}
@@ -0,0 +1,95 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::{Duration, Instant},
+};
+
+/// how long an actor waits before it is restarted by its supervisor
+/// the delay grows with the number of consecutive restarts
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Backoff {
+    /// the same delay for every restart
+    Constant(Duration),
+    /// `initial + step * n` for the n-th consecutive restart, up to `max`
+    Linear {
+        initial: Duration,
+        step: Duration,
+        max: Duration,
+    },
+    /// `initial * 2^n` for the n-th consecutive restart, up to `max`
+    /// `jitter` in `0.0..=1.0` is the part of the delay which is random,
+    /// it keeps actors failing together from restarting together
+    Exponential {
+        initial: Duration,
+        max: Duration,
+        jitter: f64,
+    },
+}
+
+impl Default for Backoff {
+    /// constant 100ms
+    fn default() -> Self {
+        Backoff::Constant(Duration::from_millis(100))
+    }
+}
+
+impl Backoff {
+    /// the delay before the n-th consecutive restart, from 0
+    pub fn delay(&self, n: u32) -> Duration {
+        match *self {
+            Backoff::Constant(delay) => delay,
+            Backoff::Linear { initial, step, max } => step
+                .checked_mul(n)
+                .and_then(|step| initial.checked_add(step))
+                .map_or(max, |delay| delay.min(max)),
+            Backoff::Exponential {
+                initial,
+                max,
+                jitter,
+            } => {
+                let delay = 2u32
+                    .checked_pow(n)
+                    .and_then(|factor| initial.checked_mul(factor))
+                    .map_or(max, |delay| delay.min(max));
+                delay.mul_f64(1.0 - jitter.clamp(0.0, 1.0) * random_fraction())
+            }
+        }
+    }
+}
+
+/// a random number in `0.0..1.0`, good enough for jitter
+fn random_fraction() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default();
+    hasher.write_u128(now.as_nanos());
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// the consecutive restarts of an actor
+#[derive(Debug, Clone, Default)]
+pub(crate) struct BackoffState {
+    attempt: u32,
+    /// when the last restart is done
+    restarted_at: Option<Instant>,
+}
+
+impl BackoffState {
+    /// the delay before the next restart
+    /// the count starts over when the actor ran for `reset_after` since the
+    /// last restart
+    pub(crate) fn next_delay(&mut self, backoff: &Backoff, reset_after: Duration) -> Duration {
+        let now = Instant::now();
+        if self
+            .restarted_at
+            .map_or(false, |at| now.saturating_duration_since(at) >= reset_after)
+        {
+            self.attempt = 0;
+        }
+        let delay = backoff.delay(self.attempt);
+        self.attempt = self.attempt.saturating_add(1);
+        self.restarted_at = Some(now + delay);
+        delay
+    }
+}
@@ -69,16 +69,16 @@ impl<S: Stream<Item = I> + Sync + Send + 'static, I: Message + Clone> Actor for
 impl<S: Stream<Item = I> + Sync + Send + 'static + Unpin, I: Message + Sync + Clone>
     StreamBroker<S, I>
 {
-    pub async fn spawn(mut self) -> anyhow::Result<(Addr, JoinHandle<anyhow::Result<()>>)> {
+    pub async fn spawn(
+        mut self,
+    ) -> anyhow::Result<(Addr<DefaultBroker<I>>, JoinHandle<anyhow::Result<()>>)> {
         let broker = DefaultBroker::<I>::new().spawn().await?;
         let broker_a = broker.clone();
         Ok((
             broker,
             tokio::spawn(async move {
                 while let Some(msg) = self.0.next().await {
-                    broker_a
-                        .call::<DefaultBroker<I>, Publish<I>>(Publish(msg))
-                        .await?;
+                    broker_a.call(Publish(msg)).await?;
                 }
                 Ok(())
             }),
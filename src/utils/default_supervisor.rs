@@ -1,36 +1,284 @@
-use std::time::Duration;
+use std::{collections::VecDeque, time::Duration};
 
-use dashmap::DashMap;
-use futures::{channel::mpsc, lock::Mutex};
-use tracing::info;
+use futures::lock::Mutex;
+use tracing::{error, info, warn};
 
+use super::backoff::{Backoff, BackoffState};
 use crate::actor::{
     addr::Addr,
     context::Context,
-    message::Handler,
-    runner::{Actor, ActorID},
-    supervisor::{Restart, Supervise, Supervisor, Unsupervise},
+    link::{spawn_monitor, Down},
+    message::{Dispatch, Handler, Shared},
+    proxy::Proxy,
+    runner::{Actor, ActorID, ActorRestart, RestartCause, StopMode},
+    supervisor::{
+        ChildCount, ChildInfo, ChildRestart, ChildSpec, ChildStatus, CountChildren, DeleteChild,
+        Restart, RestartChild, SetBackoff, StartChild, Supervise, Supervisor, TerminateChild,
+        Unsupervise, WhichChildren,
+    },
 };
 
 pub struct DefaultSupervisor {
     restart_strategy: DefaultSupervisorRestartStrategy,
-    last_restart: Mutex<std::time::Instant>,
-    restart_delay: Duration,
-    supervised_actors: DashMap<ActorID, Addr>,
+    /// the backoff of the actors without their own
+    backoff: Backoff,
+    /// how long an actor runs before its backoff starts over
+    backoff_reset: Duration,
+    /// at most this many restarts within the duration, unlimited if None
+    restart_intensity: Option<(usize, Duration)>,
+    /// the recent restarts, oldest first
+    restarts: Mutex<VecDeque<std::time::Instant>>,
+    /// in the order they are supervised
+    supervised_actors: std::sync::Mutex<Vec<SupervisedActor>>,
+    /// the children stopped when giving up, started again on restart
+    stopped_children: std::sync::Mutex<Vec<ChildSpec>>,
+}
+
+struct SupervisedActor {
+    addr: Addr,
+    /// None if the actor is not started by this supervisor
+    spec: Option<ChildSpec>,
+    /// stopped by `TerminateChild`, kept until `RestartChild` or
+    /// `DeleteChild`
+    terminated: bool,
+    restarts: usize,
+    backoff: Option<Backoff>,
+    backoff_state: BackoffState,
+}
+
+impl SupervisedActor {
+    fn new(addr: Addr, spec: Option<ChildSpec>) -> Self {
+        Self {
+            addr,
+            spec,
+            terminated: false,
+            restarts: 0,
+            backoff: None,
+            backoff_state: BackoffState::default(),
+        }
+    }
 }
 
 pub enum DefaultSupervisorRestartStrategy {
+    /// restart the failed actor only
     OneForOne,
+    /// restart all the supervised actors
     OneForAll,
+    /// restart the failed actor and the ones supervised after it
+    RestForOne,
 }
 
 impl DefaultSupervisor {
     pub fn new(restart_strategy: DefaultSupervisorRestartStrategy) -> Self {
         Self {
             restart_strategy,
-            last_restart: Mutex::new(std::time::Instant::now()),
-            restart_delay: Duration::from_millis(100),
-            supervised_actors: DashMap::new(),
+            backoff: Backoff::default(),
+            backoff_reset: Duration::from_secs(10),
+            restart_intensity: None,
+            restarts: Mutex::new(VecDeque::new()),
+            supervised_actors: std::sync::Mutex::new(vec![]),
+            stopped_children: std::sync::Mutex::new(vec![]),
+        }
+    }
+
+    /// the backoff of the supervised actors, default to a constant 100ms
+    /// `SetBackoff` overrides it for one actor
+    pub fn with_backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// start the backoff of an actor over once it runs for `period` since
+    /// its last restart, default to 10s
+    pub fn with_backoff_reset(mut self, period: Duration) -> Self {
+        self.backoff_reset = period;
+        self
+    }
+
+    /// give up after `max_restarts` restarts within `within`
+    /// the supervisor then stops its actors and fails, so its own supervisor
+    /// handles the failure when it is supervised
+    pub fn with_restart_intensity(mut self, max_restarts: usize, within: Duration) -> Self {
+        self.restart_intensity = Some((max_restarts, within));
+        self
+    }
+
+    /// record a restart, fails if it exceeds the restart intensity
+    async fn check_intensity(&self) -> anyhow::Result<()> {
+        let (max_restarts, within) = match self.restart_intensity {
+            Some(intensity) => intensity,
+            None => return Ok(()),
+        };
+        let mut restarts = self.restarts.lock().await;
+        let now = std::time::Instant::now();
+        while restarts
+            .front()
+            .map_or(false, |restart| now.duration_since(*restart) > within)
+        {
+            restarts.pop_front();
+        }
+        if restarts.len() >= max_restarts {
+            return Err(anyhow::anyhow!(
+                "error: more than {} restarts within {:?}",
+                max_restarts,
+                within
+            ));
+        }
+        restarts.push_back(now);
+        Ok(())
+    }
+
+    fn supervised_actors(&self) -> std::sync::MutexGuard<'_, Vec<SupervisedActor>> {
+        self.supervised_actors
+            .lock()
+            .expect("supervised actors lock is poisoned")
+    }
+
+    fn take_supervised_actors(&self) -> Vec<SupervisedActor> {
+        std::mem::take(&mut *self.supervised_actors())
+    }
+
+    /// start the child of `spec` and supervise it at `index`, or after the
+    /// other actors
+    /// `restarts` is the number of times it is already restarted
+    async fn start_child(
+        &self,
+        ctx: &Context,
+        spec: ChildSpec,
+        index: Option<usize>,
+        restarts: usize,
+    ) -> anyhow::Result<Addr> {
+        let addr = spec.start(ctx.system().runner()).await?;
+        addr.add_supervisor(
+            ctx.address()
+                .expect("supervisor should be in the context")
+                .proxy::<DefaultSupervisor, Restart>()
+                .await,
+        )
+        .await;
+        Self::monitor(ctx, &addr);
+        let actor = SupervisedActor {
+            restarts,
+            backoff: spec.backoff,
+            ..SupervisedActor::new(addr.clone(), Some(spec))
+        };
+        let mut actors = self.supervised_actors();
+        match index {
+            Some(index) if index < actors.len() => actors.insert(index, actor),
+            _ => actors.push(actor),
+        }
+        Ok(addr)
+    }
+
+    /// get `Down` when `addr` exits
+    fn monitor(ctx: &Context, addr: &Addr) {
+        let id = addr.id;
+        spawn_monitor(
+            addr.rx_exit.clone(),
+            ctx.rx_exit.clone(),
+            Proxy::from_mailbox(
+                ctx.id,
+                ctx.tx.clone(),
+                <Self as Dispatch<Down, Shared>>::exec_event,
+            ),
+            move |reason| Some(Down { id, reason }),
+        );
+    }
+
+    /// the restart type of the actor with `id`, None if it is not started
+    /// from a spec
+    fn child_restart(&self, id: ActorID) -> Option<ChildRestart> {
+        self.supervised_actors()
+            .iter()
+            .find(|actor| actor.addr.id == id)?
+            .spec
+            .as_ref()
+            .map(|spec| spec.restart)
+    }
+
+    /// the delay before restarting the actor with `id`
+    fn next_delay(&self, id: ActorID) -> Duration {
+        let mut actors = self.supervised_actors();
+        match actors.iter_mut().find(|actor| actor.addr.id == id) {
+            Some(actor) => {
+                let backoff = actor.backoff.unwrap_or(self.backoff);
+                actor.backoff_state.next_delay(&backoff, self.backoff_reset)
+            }
+            None => self.backoff.delay(0),
+        }
+    }
+
+    /// the actors to restart along with the failed one, in supervision order
+    fn siblings_to_restart(&self, failed: ActorID) -> Vec<Addr> {
+        let actors = self.supervised_actors();
+        let siblings: Box<dyn Iterator<Item = &SupervisedActor>> = match self.restart_strategy {
+            DefaultSupervisorRestartStrategy::OneForOne => return vec![],
+            DefaultSupervisorRestartStrategy::OneForAll => {
+                Box::new(actors.iter().filter(|actor| actor.addr.id != failed))
+            }
+            DefaultSupervisorRestartStrategy::RestForOne => Box::new(
+                actors
+                    .iter()
+                    .skip_while(|actor| actor.addr.id != failed)
+                    .skip(1),
+            ),
+        };
+        siblings
+            .filter(|actor| !actor.terminated)
+            .map(|actor| actor.addr.clone())
+            .collect()
+    }
+
+    fn count_restart(&self, id: ActorID) {
+        if let Some(actor) = self
+            .supervised_actors()
+            .iter_mut()
+            .find(|actor| actor.addr.id == id)
+        {
+            actor.restarts += 1;
+        }
+    }
+
+    /// stop a child and wait for it up to its shutdown timeout, then abort it
+    async fn shutdown_child(addr: &Addr, spec: &ChildSpec) {
+        addr.clone().stop_with_mode(Ok(()), StopMode::Immediate);
+        if tokio::time::timeout(spec.shutdown, addr.await_stop())
+            .await
+            .is_err()
+        {
+            // like a shutdown deadline, `on_stop` may be the one stuck
+            warn!(
+                "{} is killed after {:?}",
+                addr.get_name_or_id_string(),
+                spec.shutdown
+            );
+            addr.system().abort_actor(addr.id);
+        }
+    }
+
+    /// stop the supervised actors without waiting for them, one of them may
+    /// be waiting for this supervisor
+    /// the specs are kept to start the children again on restart
+    fn stop_supervised_actors(&self) {
+        let mut stopped_children = self
+            .stopped_children
+            .lock()
+            .expect("stopped children lock is poisoned");
+        for actor in self.take_supervised_actors() {
+            actor.addr.stop_with_mode(Ok(()), StopMode::Immediate);
+            stopped_children.extend(actor.spec);
+        }
+    }
+
+    /// stop the supervised actors and fail the supervisor
+    fn give_up(&self, ctx: &Context, err: &anyhow::Error) {
+        error!("{} gives up: {:#}", self.get_name_or_id_string(ctx), err);
+        self.stop_supervised_actors();
+        if let Some(addr) = ctx.address() {
+            addr.stop_with_mode(
+                Err(anyhow::anyhow!("supervisor gave up: {:#}", err)),
+                StopMode::Immediate,
+            );
         }
     }
 }
@@ -43,31 +291,44 @@ impl Actor for DefaultSupervisor {
     )]
     async fn on_stop(&self, _ctx: &Context) {
         info!("{} stop", self.get_name_or_id_string(_ctx));
-        self.supervised_actors.clear();
+        // the children are stopped one by one in reverse start order
+        for actor in self.take_supervised_actors().into_iter().rev() {
+            if let Some(spec) = &actor.spec {
+                Self::shutdown_child(&actor.addr, spec).await;
+            }
+        }
     }
 }
 
 #[async_trait::async_trait]
 impl Handler<Restart> for DefaultSupervisor {
-    async fn handle(&self, _ctx: &Context, msg: Restart) -> anyhow::Result<anyhow::Result<()>> {
-        let mut last_restart = self.last_restart.lock().await;
-        while last_restart.elapsed() < self.restart_delay {
-            tokio::time::sleep(self.restart_delay - last_restart.elapsed()).await;
+    async fn handle(&self, ctx: &Context, msg: Restart) -> anyhow::Result<anyhow::Result<()>> {
+        if self.child_restart(msg.0) == Some(ChildRestart::Temporary) {
+            self.supervised_actors()
+                .retain(|actor| actor.addr.id != msg.0);
+            return Ok(Err(anyhow::anyhow!(
+                "error: temporary actor {} is not restarted",
+                msg.0
+            )));
+        }
+        if let Err(err) = self.check_intensity().await {
+            self.give_up(ctx, &err);
+            return Ok(Err(err));
         }
+        // the actors wait for the delay on their own, so the backoff of one
+        // actor never holds up the others
+        let delay = self.next_delay(msg.0);
+        ctx.system().delay_restart(msg.0, delay);
         let mut inner_err = Ok(());
-        match self.restart_strategy {
-            DefaultSupervisorRestartStrategy::OneForOne => {}
-            DefaultSupervisorRestartStrategy::OneForAll => {
-                for addr in &self.supervised_actors {
-                    if addr.id != msg.0 {
-                        inner_err = mpsc::UnboundedSender::clone(&*addr.tx)
-                            .start_send(crate::actor::addr::Event::Restart);
-                    }
-                }
+        self.count_restart(msg.0);
+        for addr in self.siblings_to_restart(msg.0) {
+            // restarted together with the failed actor
+            ctx.system().delay_restart(addr.id, delay);
+            match addr.tx.send_system(crate::actor::addr::Event::Restart) {
+                Ok(()) => self.count_restart(addr.id),
+                Err(err) => inner_err = Err(err),
             }
         }
-
-        *last_restart = std::time::Instant::now();
         Ok(inner_err.map_err(|e| e.into()))
     }
 }
@@ -77,16 +338,18 @@ impl Handler<Supervise> for DefaultSupervisor {
     async fn handle(&self, ctx: &Context, msg: Supervise) -> anyhow::Result<()> {
         msg.0
             .add_supervisor(
-                ctx.addr
-                    .get()
-                    .expect("supervisor should be in the context")
-                    .upgrade()
+                ctx.address()
                     .expect("supervisor should be in the context")
                     .proxy::<DefaultSupervisor, Restart>()
                     .await,
             )
             .await;
-        self.supervised_actors.insert(msg.0.id, msg.0);
+        let mut actors = self.supervised_actors();
+        if actors.iter().all(|actor| actor.addr.id != msg.0.id) {
+            // forgotten once it exits, see `Handler<Down>`
+            Self::monitor(ctx, &msg.0);
+            actors.push(SupervisedActor::new(msg.0, None));
+        }
         Ok(())
     }
 }
@@ -94,9 +357,211 @@ impl Handler<Supervise> for DefaultSupervisor {
 #[async_trait::async_trait]
 impl Handler<Unsupervise> for DefaultSupervisor {
     async fn handle(&self, _ctx: &Context, msg: Unsupervise) -> anyhow::Result<()> {
-        self.supervised_actors.remove(&msg.0.id);
+        self.supervised_actors()
+            .retain(|actor| actor.addr.id != msg.0.id);
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl Handler<StartChild> for DefaultSupervisor {
+    async fn handle(&self, ctx: &Context, msg: StartChild) -> anyhow::Result<Addr> {
+        self.start_child(ctx, msg.0, None, 0).await
+    }
+}
+
+/// a child started from a spec exited, the failures restarted in place never
+/// get here
+/// nothing is started again while the system shuts down
+#[async_trait::async_trait]
+impl Handler<Down> for DefaultSupervisor {
+    async fn handle(&self, ctx: &Context, msg: Down) -> anyhow::Result<()> {
+        let (index, spec, restarts) = {
+            let mut actors = self.supervised_actors();
+            let index = match actors
+                .iter()
+                .position(|actor| actor.addr.id == msg.id && !actor.terminated)
+            {
+                Some(index) => index,
+                // stopped by this supervisor
+                None => return Ok(()),
+            };
+            let actor = actors.remove(index);
+            (index, actor.spec, actor.restarts)
+        };
+        let spec = match spec {
+            // stopped by the shutdown of the system
+            _ if ctx.system().is_shutting_down() => return Ok(()),
+            Some(spec)
+                if spec.restart == ChildRestart::Permanent
+                    || (spec.restart == ChildRestart::Transient && !msg.reason.is_normal()) =>
+            {
+                spec
+            }
+            _ => return Ok(()),
+        };
+        if let Err(err) = self.check_intensity().await {
+            self.give_up(ctx, &err);
+            return Ok(());
+        }
+        if let Err(err) = self.start_child(ctx, spec, Some(index), restarts + 1).await {
+            error!(
+                "{} failed to start a child again: {:#}",
+                self.get_name_or_id_string(ctx),
+                err
+            );
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl Handler<WhichChildren> for DefaultSupervisor {
+    async fn handle(&self, _ctx: &Context, _msg: WhichChildren) -> anyhow::Result<Vec<ChildInfo>> {
+        Ok(self
+            .supervised_actors()
+            .iter()
+            .map(|actor| ChildInfo {
+                id: actor.addr.id,
+                name: actor.addr.get_name(),
+                status: match actor.terminated {
+                    true => ChildStatus::Terminated,
+                    false => ChildStatus::Running,
+                },
+                restarts: actor.restarts,
+            })
+            .collect())
+    }
+}
+
+#[async_trait::async_trait]
+impl Handler<CountChildren> for DefaultSupervisor {
+    async fn handle(&self, _ctx: &Context, _msg: CountChildren) -> anyhow::Result<ChildCount> {
+        let actors = self.supervised_actors();
+        Ok(ChildCount {
+            total: actors.len(),
+            running: actors.iter().filter(|actor| !actor.terminated).count(),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl Handler<TerminateChild> for DefaultSupervisor {
+    async fn handle(&self, _ctx: &Context, msg: TerminateChild) -> anyhow::Result<()> {
+        let (addr, spec) = {
+            let mut actors = self.supervised_actors();
+            let index = actors
+                .iter()
+                .position(|actor| actor.addr.id == msg.0 && !actor.terminated)
+                .ok_or_else(|| anyhow::anyhow!("error: actor {} is not running", msg.0))?;
+            let actor = &mut actors[index];
+            match &actor.spec {
+                Some(spec) => {
+                    actor.terminated = true;
+                    (actor.addr.clone(), Some(spec.clone()))
+                }
+                None => (actors.remove(index).addr, None),
+            }
+        };
+        match spec {
+            Some(spec) => Self::shutdown_child(&addr, &spec).await,
+            // it may be waiting for this supervisor
+            None => addr.stop_with_mode(Ok(()), StopMode::Immediate),
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl Handler<RestartChild> for DefaultSupervisor {
+    async fn handle(&self, ctx: &Context, msg: RestartChild) -> anyhow::Result<Addr> {
+        let (index, spec, restarts) = {
+            let mut actors = self.supervised_actors();
+            let index = actors
+                .iter()
+                .position(|actor| actor.addr.id == msg.0)
+                .ok_or_else(|| anyhow::anyhow!("error: actor {} is not supervised", msg.0))?;
+            let actor = &mut actors[index];
+            if !actor.terminated {
+                actor
+                    .addr
+                    .tx
+                    .send_system(crate::actor::addr::Event::Restart)?;
+                actor.restarts += 1;
+                return Ok(actor.addr.clone());
+            }
+            let spec = actor
+                .spec
+                .clone()
+                .expect("only a child started from a spec is terminated");
+            (index, spec, actor.restarts)
+        };
+        // the terminated one is kept if the child fails to start
+        let addr = self
+            .start_child(ctx, spec, Some(index), restarts + 1)
+            .await?;
+        self.supervised_actors()
+            .retain(|actor| actor.addr.id != msg.0);
+        Ok(addr)
+    }
+}
+
+#[async_trait::async_trait]
+impl Handler<DeleteChild> for DefaultSupervisor {
+    async fn handle(&self, _ctx: &Context, msg: DeleteChild) -> anyhow::Result<()> {
+        let mut actors = self.supervised_actors();
+        let index = actors
+            .iter()
+            .position(|actor| actor.addr.id == msg.0)
+            .ok_or_else(|| anyhow::anyhow!("error: actor {} is not supervised", msg.0))?;
+        if !actors[index].terminated {
+            return Err(anyhow::anyhow!("error: actor {} is running", msg.0));
+        }
+        actors.remove(index);
         Ok(())
     }
 }
 
+#[async_trait::async_trait]
+impl Handler<SetBackoff> for DefaultSupervisor {
+    async fn handle(&self, _ctx: &Context, msg: SetBackoff) -> anyhow::Result<()> {
+        let mut actors = self.supervised_actors();
+        let actor = actors
+            .iter_mut()
+            .find(|actor| actor.addr.id == msg.0)
+            .ok_or_else(|| anyhow::anyhow!("error: actor {} is not supervised", msg.0))?;
+        actor.backoff = Some(msg.1);
+        actor.backoff_state = BackoffState::default();
+        Ok(())
+    }
+}
+
+/// restarted by its own supervisor, the supervised actors are stopped and
+/// only the children started from a spec are started again
+#[async_trait::async_trait]
+impl ActorRestart for DefaultSupervisor {
+    async fn pre_restart(&self, _ctx: &Context, _cause: &RestartCause) {
+        self.stop_supervised_actors();
+        self.restarts.lock().await.clear();
+    }
+
+    async fn post_restart(&self, ctx: &Context) {
+        let children = std::mem::take(
+            &mut *self
+                .stopped_children
+                .lock()
+                .expect("stopped children lock is poisoned"),
+        );
+        for spec in children {
+            if let Err(err) = self.start_child(ctx, spec, None, 0).await {
+                error!(
+                    "{} failed to start a child again: {:#}",
+                    self.get_name_or_id_string(ctx),
+                    err
+                );
+            }
+        }
+    }
+}
+
 impl Supervisor for DefaultSupervisor {}
@@ -1,3 +1,4 @@
+pub mod backoff;
 pub mod default_broker;
 pub mod default_supervisor;
 pub mod service;
@@ -1,13 +1,22 @@
-use std::{any::TypeId, lazy::SyncLazy, sync::Arc};
+use std::{any::TypeId, sync::Arc};
 
 use anyhow::Result;
 use dashmap::DashMap;
 
-use crate::actor::{addr::Addr, runner::Actor};
+use crate::actor::{
+    addr::Addr,
+    runner::Actor,
+    system::{ActorSystem, GlobalShim},
+};
 
+/// the global registry is `ActorSystem::service_registry` of the default
+/// system
 pub type Registry = DashMap<TypeId, Arc<dyn Service>>;
 
-pub static GLOBAL_SERVICE_REGISTRY: SyncLazy<Registry> = SyncLazy::new(DashMap::new);
+/// the service registry of the default system
+#[deprecated(note = "use `ActorSystem::service_registry`")]
+pub static GLOBAL_SERVICE_REGISTRY: GlobalShim<Registry> =
+    GlobalShim(ActorSystem::service_registry);
 
 thread_local! {
     static LOCAL_SERVICE_REGISTRY: Registry = DashMap::new();
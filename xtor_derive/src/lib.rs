@@ -9,15 +9,29 @@ use syn::{
 
 /// Xtor Message Derive
 ///
+/// `error_policy` could be one of `reply_only`, `stop`, `restart` and
+/// `escalate`, it overrides the error policy of the actor for this message.
+///
+/// `priority` could be `normal` (default) or `high`, high priority messages
+/// overtake the normal ones queued in the mailbox.
+///
 /// # Examples
 /// ```ignore
 /// #[message(result = "i32")]
 /// struct TestMessage(i32);
+///
+/// #[message(result = "i32", error_policy = "reply_only")]
+/// struct Validate(i32);
+///
+/// #[message(result = "()", priority = "high")]
+/// struct Pause;
 /// ```
 #[proc_macro_attribute]
 pub fn message(args: TokenStream, input: TokenStream) -> TokenStream {
     let args = parse_macro_input!(args as AttributeArgs);
     let mut result_type = quote! { () };
+    let mut error_policy = quote! {};
+    let mut priority = quote! {};
 
     for arg in args {
         if let NestedMeta::Meta(Meta::NameValue(nv)) = arg {
@@ -34,6 +48,49 @@ pub fn message(args: TokenStream, input: TokenStream) -> TokenStream {
                         .into();
                     }
                 }
+            } else if nv.path.is_ident("error_policy") {
+                if let syn::Lit::Str(lit) = nv.lit {
+                    let policy = match lit.value().as_str() {
+                        "reply_only" => quote! { ReplyOnly },
+                        "stop" => quote! { Stop },
+                        "restart" => quote! { Restart },
+                        "escalate" => quote! { Escalate },
+                        other => {
+                            return Error::new_spanned(
+                                &lit,
+                                format!(
+                                    "Expect one of reply_only, stop, restart, escalate found {:?}",
+                                    other
+                                ),
+                            )
+                            .to_compile_error()
+                            .into();
+                        }
+                    };
+                    error_policy = quote! {
+                        const ERROR_POLICY: Option<xtor::actor::runner::ErrorPolicy> =
+                            Some(xtor::actor::runner::ErrorPolicy::#policy);
+                    };
+                }
+            } else if nv.path.is_ident("priority") {
+                if let syn::Lit::Str(lit) = nv.lit {
+                    let lane = match lit.value().as_str() {
+                        "normal" => quote! { Normal },
+                        "high" => quote! { High },
+                        other => {
+                            return Error::new_spanned(
+                                &lit,
+                                format!("Expect one of normal, high found {:?}", other),
+                            )
+                            .to_compile_error()
+                            .into();
+                        }
+                    };
+                    priority = quote! {
+                        const PRIORITY: xtor::actor::mailbox::Priority =
+                            xtor::actor::mailbox::Priority::#lane;
+                    };
+                }
             }
         }
     }
@@ -44,6 +101,8 @@ pub fn message(args: TokenStream, input: TokenStream) -> TokenStream {
         #input
         impl xtor::actor::message::Message for #ident {
             type Result = #result_type;
+            #error_policy
+            #priority
         }
     };
     expanded.into()
@@ -88,6 +147,7 @@ pub fn main(_args: TokenStream, input: TokenStream) -> TokenStream {
 }
 
 /// Xtor test derive
+/// each test runs in its own `ActorSystem` and only waits for the actors of it
 /// # Examples
 /// ```ignore
 /// #[xtor::test]
@@ -115,7 +175,7 @@ pub fn test(_args: TokenStream, input: TokenStream) -> TokenStream {
         #[test]
         fn #ident() #ret {
             #input
-            xtor::block_on(async{let res = __test().await; xtor::await_exit().await; res})
+            xtor::block_on(xtor::run_in_new_system(__test()))
         }
     };
 